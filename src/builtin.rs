@@ -0,0 +1,63 @@
+use crate::expression_result::{ExpressionResult, MapKey};
+use crate::r#type::Type;
+
+// Functions provided by the language, a user function with the same name takes precedence
+
+pub fn signature(name: &str) -> Option<(Vec<Type>, Type)> {
+	match name {
+		"wrapping_add" | "wrapping_sub" | "wrapping_mul" |
+		"saturating_add" | "saturating_sub" | "saturating_mul" => Some((Vec::from([Type::Int, Type::Int]), Type::Int)),
+		"parse_int" => Some((Vec::from([Type::String]), Type::Result(Box::new(Type::Int), Box::new(Type::String)))),
+		_ => None
+	}
+}
+
+pub fn is_builtin(name: &str) -> bool {
+	matches!(name, "len" | "contains" | "remove") || signature(name).is_some()
+}
+
+pub fn return_type(name: &str, arg_types: &[Type]) -> Result<Type, String> {
+	match (name, arg_types) {
+		// Work on any element type
+		("len", [Type::Array(_) | Type::Map(_, _) | Type::String]) => Ok(Type::Int),
+		("len", _) => Err(format!("len expects one array, map or string argument, got {:?}", arg_types)),
+		("contains", [Type::Map(key_type, _), key]) if **key_type == *key => Ok(Type::Bool),
+		("remove", [Type::Map(key_type, value_type), key]) if **key_type == *key => Ok(Type::Map(key_type.clone(), value_type.clone())),
+		("contains" | "remove", _) => Err(format!("{} expects a map and one of its keys, got {:?}", name, arg_types)),
+		_ => match signature(name) {
+			Some((param_types, return_type)) if param_types == arg_types => Ok(return_type),
+			Some((param_types, _)) => Err(format!("Wrong arguments to call {}, expected {:?}, got {:?}", name, param_types, arg_types)),
+			None => Err(format!("Undefined function {}", name))
+		}
+	}
+}
+
+pub fn call(name: &str, args: &[ExpressionResult]) -> Result<ExpressionResult, String> {
+	match (name, args) {
+		("wrapping_add", [ExpressionResult::Int(lhs), ExpressionResult::Int(rhs)]) => Ok(ExpressionResult::Int(lhs.wrapping_add(*rhs))),
+		("wrapping_sub", [ExpressionResult::Int(lhs), ExpressionResult::Int(rhs)]) => Ok(ExpressionResult::Int(lhs.wrapping_sub(*rhs))),
+		("wrapping_mul", [ExpressionResult::Int(lhs), ExpressionResult::Int(rhs)]) => Ok(ExpressionResult::Int(lhs.wrapping_mul(*rhs))),
+		("saturating_add", [ExpressionResult::Int(lhs), ExpressionResult::Int(rhs)]) => Ok(ExpressionResult::Int(lhs.saturating_add(*rhs))),
+		("saturating_sub", [ExpressionResult::Int(lhs), ExpressionResult::Int(rhs)]) => Ok(ExpressionResult::Int(lhs.saturating_sub(*rhs))),
+		("saturating_mul", [ExpressionResult::Int(lhs), ExpressionResult::Int(rhs)]) => Ok(ExpressionResult::Int(lhs.saturating_mul(*rhs))),
+		// A malformed number is an error value for the program, not a runtime error
+		("parse_int", [ExpressionResult::String(value)]) => match value.parse::<i64>() {
+			Ok(number) => Ok(ExpressionResult::Ok(Box::new(ExpressionResult::Int(number)))),
+			Err(_) => Ok(ExpressionResult::Err(Box::new(ExpressionResult::String(format!("Invalid integer {:?}", value)))))
+		},
+		("len", [ExpressionResult::Array(elements)]) => Ok(ExpressionResult::Int(elements.len() as i64)),
+		("len", [ExpressionResult::Map(entries)]) => Ok(ExpressionResult::Int(entries.len() as i64)),
+		("len", [ExpressionResult::String(value)]) => Ok(ExpressionResult::Int(value.chars().count() as i64)),
+		("contains", [ExpressionResult::Map(entries), key]) => Ok(ExpressionResult::Bool(MapKey::from_result(key).is_some_and(|key| entries.contains_key(&key)))),
+		// Maps are values, the map without the key is returned
+		("remove", [ExpressionResult::Map(entries), key]) => {
+			let mut entries = entries.clone();
+			if let Some(key) = MapKey::from_result(key) {
+				entries.remove(&key);
+			}
+
+			Ok(ExpressionResult::Map(entries))
+		},
+		_ => Err(format!("Wrong arguments to call {}", name))
+	}
+}
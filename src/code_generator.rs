@@ -0,0 +1,804 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::node::{Node, Accessor, Pattern, TypeParam};
+use crate::operator::Operator;
+use crate::visitor::{Visitor, Visitable};
+use crate::r#type::Type;
+use crate::span::Span;
+use crate::scope::{Scope, Scopes};
+use crate::optimizer::OptimizerVisitor;
+
+// System V AMD64 calling convention, remaining arguments go on the stack
+const ARG_REGISTERS: [&str; 6] = ["%rdi", "%rsi", "%rdx", "%rcx", "%r8", "%r9"];
+const WORD_SIZE: i64 = 8;
+
+struct Frame {
+	label: String,
+	code: String,
+	stack_size: i64,
+	// Number of words pushed on top of the frame, needed to keep calls aligned on 16 bytes
+	depth: usize
+}
+
+impl Frame {
+	fn new(label: &str) -> Frame {
+		Frame {
+			label: String::from(label),
+			code: String::new(),
+			stack_size: 0,
+			depth: 0
+		}
+	}
+
+	fn return_label(&self) -> String {
+		format!(".Lreturn_{}", self.label)
+	}
+
+	fn allocate(&mut self) -> i64 {
+		self.stack_size += WORD_SIZE;
+
+		-self.stack_size
+	}
+
+	fn assemble(&self) -> String {
+		// Keep %rsp aligned on 16 bytes once the prologue is done
+		let stack_size = (self.stack_size + 15) / 16 * 16;
+
+		let mut output = String::new();
+		output.push_str(&format!("{}:\n", self.label));
+		output.push_str("\tpushq %rbp\n");
+		output.push_str("\tmovq %rsp, %rbp\n");
+		if stack_size > 0 {
+			output.push_str(&format!("\tsubq ${}, %rsp\n", stack_size));
+		}
+		output.push_str(&self.code);
+		output.push_str("\txorl %eax, %eax\n");
+		output.push_str(&format!("{}:\n", self.return_label()));
+		output.push_str("\tmovq %rbp, %rsp\n");
+		output.push_str("\tpopq %rbp\n");
+		output.push_str("\tret\n");
+
+		output
+	}
+}
+
+pub struct CodeGeneratorVisitor {
+	frames: Vec<Frame>,
+	// Stack offsets of the variables and labels of the functions, scoped like in the interpretor
+	scopes: Scopes<i64, String>,
+	function_labels: HashSet<String>,
+	assembled_functions: Vec<String>,
+	// Messages of the runtime errors the program can stop with, the index gives the label of their stub
+	runtime_errors: Vec<&'static str>,
+	// Values of the constants, computed once at their declaration
+	constants: HashMap<String, Node>,
+	label_count: usize
+}
+
+impl Default for CodeGeneratorVisitor {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl CodeGeneratorVisitor {
+	pub fn new() -> CodeGeneratorVisitor {
+		CodeGeneratorVisitor {
+			frames: Vec::new(),
+			scopes: Scopes::new(),
+			function_labels: HashSet::new(),
+			assembled_functions: Vec::new(),
+			runtime_errors: Vec::new(),
+			constants: HashMap::new(),
+			label_count: 0
+		}
+	}
+
+	// Produce a GNU as (AT&T syntax) x86-64 program, top level instructions become `main`
+	pub fn generate(&mut self, ast: &Node) -> String {
+		self.frames.push(Frame::new("main"));
+		ast.accept(self);
+		let main = self.frames.pop().expect("No frame");
+
+		let mut output = String::from("\t.text\n\t.globl main\n");
+		output.push_str(&main.assemble());
+		for function in self.assembled_functions.iter() {
+			output.push_str(function);
+		}
+		output.push_str(&self.assemble_runtime_errors());
+		output.push_str("\t.section .note.GNU-stack,\"\",@progbits\n");
+
+		output
+	}
+
+	fn frame(&mut self) -> &mut Frame {
+		match self.frames.last_mut() {
+			Some(frame) => frame,
+			None => panic!("No frame")
+		}
+	}
+
+	fn emit(&mut self, instruction: &str) {
+		let frame = self.frame();
+		frame.code.push('\t');
+		frame.code.push_str(instruction);
+		frame.code.push('\n');
+	}
+
+	fn emit_label(&mut self, label: &str) {
+		let frame = self.frame();
+		frame.code.push_str(label);
+		frame.code.push_str(":\n");
+	}
+
+	fn push(&mut self, register: &str) {
+		self.emit(&format!("pushq {}", register));
+		self.frame().depth += 1;
+	}
+
+	fn pop(&mut self, register: &str) {
+		self.emit(&format!("popq {}", register));
+		self.frame().depth -= 1;
+	}
+
+	fn new_label(&mut self, kind: &str) -> String {
+		self.label_count += 1;
+
+		format!(".L{}{}", kind, self.label_count)
+	}
+
+	fn function_label(&mut self, name: &str) -> String {
+		let mut label = format!("jpl_{}", name);
+		if self.function_labels.contains(&label) {
+			// Same name declared in another scope, keep labels unique
+			self.label_count += 1;
+			label = format!("jpl_{}_{}", name, self.label_count);
+		}
+		self.function_labels.insert(label.clone());
+
+		label
+	}
+
+	fn declare_var(&mut self, name: &str) -> i64 {
+		let offset = self.frame().allocate();
+		self.scopes.insert_var(name, offset);
+
+		offset
+	}
+
+	fn runtime_error_label(&mut self, message: &'static str) -> String {
+		let index = match self.runtime_errors.iter().position(|error| *error == message) {
+			Some(index) => index,
+			None => {
+				self.runtime_errors.push(message);
+				self.runtime_errors.len() - 1
+			}
+		};
+
+		format!(".Lruntime_error{}", index)
+	}
+
+	// Stop the program with a runtime error when `condition_jump` is taken
+	fn check(&mut self, condition_jump: &str, message: &'static str) {
+		let label = self.runtime_error_label(message);
+		self.emit(&format!("{} {}", condition_jump, label));
+	}
+
+	// The stubs print their message on stderr and exit with 1, like the interpretor does
+	fn assemble_runtime_errors(&self) -> String {
+		if self.runtime_errors.is_empty() {
+			return String::new();
+		}
+
+		let mut output = String::new();
+		for (index, message) in self.runtime_errors.iter().enumerate() {
+			let message = format!("Runtime error: {}\n", message);
+			output.push_str(&format!(".Lruntime_error{}:\n", index));
+			output.push_str(&format!("\tleaq .Lruntime_error_message{}(%rip), %rsi\n", index));
+			output.push_str(&format!("\tmovq ${}, %rdx\n", message.len()));
+			output.push_str("\tjmp .Lruntime_error_exit\n");
+		}
+		// Direct system calls, the stack may not be aligned for a libc call where the error is raised
+		output.push_str(".Lruntime_error_exit:\n");
+		output.push_str("\tmovq $2, %rdi\n");
+		output.push_str("\tmovq $1, %rax\n");
+		output.push_str("\tsyscall\n");
+		output.push_str("\tmovq $1, %rdi\n");
+		output.push_str("\tmovq $60, %rax\n");
+		output.push_str("\tsyscall\n");
+
+		output.push_str("\t.section .rodata\n");
+		for (index, message) in self.runtime_errors.iter().enumerate() {
+			output.push_str(&format!(".Lruntime_error_message{}:\n", index));
+			output.push_str(&format!("\t.ascii \"Runtime error: {}\\n\"\n", message));
+		}
+		output.push_str("\t.text\n");
+
+		output
+	}
+
+	// Division by zero and `i64::MIN / -1` are runtime errors instead of a crash of the program
+	fn check_divisor(&mut self) {
+		let divide_label = self.new_label("divide");
+
+		self.emit("testq %rcx, %rcx");
+		self.check("jz", "Division by zero");
+		self.emit("cmpq $-1, %rcx");
+		self.emit(&format!("jne {}", divide_label));
+		self.emit("movq %rax, %rdx");
+		self.emit("negq %rdx");
+		self.check("jo", "Integer overflow");
+		self.emit_label(&divide_label);
+	}
+
+	fn unsupported(what: &str) -> ! {
+		panic!("{} is not supported by the assembly backend", what)
+	}
+
+	fn apply_binary_op(&mut self, op: &Operator) {
+		match op {
+			Operator::Add => {
+				self.emit("addq %rcx, %rax");
+				self.check("jo", "Integer overflow");
+			},
+			Operator::Minus => {
+				self.emit("subq %rcx, %rax");
+				self.check("jo", "Integer overflow");
+			},
+			Operator::Product => {
+				self.emit("imulq %rcx, %rax");
+				self.check("jo", "Integer overflow");
+			},
+			Operator::Divide => {
+				self.check_divisor();
+				self.emit("cqto");
+				self.emit("idivq %rcx");
+			},
+			Operator::Modulo => {
+				self.check_divisor();
+				self.emit("cqto");
+				self.emit("idivq %rcx");
+				self.emit("movq %rdx, %rax");
+			},
+			Operator::Power => {
+				let loop_label = self.new_label("power");
+				let skip_label = self.new_label("powerskip");
+				let end_label = self.new_label("endpower");
+
+				self.emit("cmpq $0, %rcx");
+				self.check("jl", "Negative exponent");
+				// The interpretor takes the exponent as an u32
+				self.emit("movl $0xffffffff, %edx");
+				self.emit("cmpq %rdx, %rcx");
+				self.check("ja", "Integer overflow");
+
+				// Exponentiation by squaring, the base is only squared when a bit of the exponent is left to use it
+				self.emit("movq %rax, %rdx");
+				self.emit("movq $1, %rax");
+				self.emit_label(&loop_label);
+				self.emit("testq $1, %rcx");
+				self.emit(&format!("jz {}", skip_label));
+				self.emit("imulq %rdx, %rax");
+				self.check("jo", "Integer overflow");
+				self.emit_label(&skip_label);
+				self.emit("shrq $1, %rcx");
+				self.emit(&format!("jz {}", end_label));
+				self.emit("imulq %rdx, %rdx");
+				self.check("jo", "Integer overflow");
+				self.emit(&format!("jmp {}", loop_label));
+				self.emit_label(&end_label);
+			},
+			Operator::BitwiseAnd => self.emit("andq %rcx, %rax"),
+			Operator::BitwiseOr => self.emit("orq %rcx, %rax"),
+			Operator::BitwiseXor => self.emit("xorq %rcx, %rax"),
+			Operator::ShiftLeft | Operator::ShiftRight => {
+				// Unsigned, a negative amount is out of range too
+				self.emit("cmpq $63, %rcx");
+				self.check("ja", "Shift amount out of range");
+
+				if *op == Operator::ShiftLeft {
+					self.emit("shlq %cl, %rax");
+				}
+				else {
+					self.emit("sarq %cl, %rax");
+				}
+			},
+			Operator::Equal => self.apply_comparison("sete"),
+			Operator::NotEqual => self.apply_comparison("setne"),
+			Operator::LowerOrEq => self.apply_comparison("setle"),
+			Operator::GreaterOrEq => self.apply_comparison("setge"),
+			Operator::Lower => self.apply_comparison("setl"),
+			Operator::Greater => self.apply_comparison("setg"),
+			_ => panic!("Wrong op")
+		}
+	}
+
+	fn apply_comparison(&mut self, set_instruction: &str) {
+		self.emit("cmpq %rcx, %rax");
+		self.emit(&format!("{} %al", set_instruction));
+		self.emit("movzbq %al, %rax");
+	}
+}
+
+impl Visitor for CodeGeneratorVisitor {
+	fn visit_int(&mut self, value: i64) {
+		if i32::try_from(value).is_ok() {
+			self.emit(&format!("movq ${}, %rax", value));
+		}
+		else {
+			self.emit(&format!("movabsq ${}, %rax", value));
+		}
+	}
+
+	fn visit_float(&mut self, _: f64) {
+		Self::unsupported("Float");
+	}
+
+	fn visit_identifier(&mut self, name: &str, _: &Span) {
+		match self.scopes.resolve_var(name).copied() {
+			Some(offset) => self.emit(&format!("movq {}(%rbp), %rax", offset)),
+			None => match self.constants.get(name).cloned() {
+				Some(value) => value.accept(self),
+				None => panic!("Not declared identifier {}", name)
+			}
+		}
+	}
+
+	fn visit_bool(&mut self, value: bool) {
+		self.emit(&format!("movq ${}, %rax", value as i64));
+	}
+
+	fn visit_binary_op(&mut self, op: &Operator, left: &Node, right: &Node, _: &Span) {
+		if *op == Operator::LogicalAnd || *op == Operator::LogicalOr {
+			// Short-circuit, %rax already holds the result when the right operand is skipped
+			let end_label = self.new_label("shortcircuit");
+
+			left.accept(self);
+			self.emit("cmpq $0, %rax");
+			if *op == Operator::LogicalAnd {
+				self.emit(&format!("je {}", end_label));
+			}
+			else {
+				self.emit(&format!("jne {}", end_label));
+			}
+
+			right.accept(self);
+			self.emit_label(&end_label);
+
+			return;
+		}
+
+		left.accept(self);
+		self.push("%rax");
+
+		right.accept(self);
+		self.emit("movq %rax, %rcx");
+		self.pop("%rax");
+
+		self.apply_binary_op(op);
+	}
+
+	fn visit_unary_op(&mut self, op: &Operator, right: &Node, _: &Span) {
+		right.accept(self);
+
+		match op {
+			Operator::Minus => {
+				self.emit("negq %rax");
+				self.check("jo", "Integer overflow");
+			},
+			Operator::Not => self.emit("xorq $1, %rax"),
+			Operator::BitwiseNot => self.emit("notq %rax"),
+			_ => panic!("No valid unary operator")
+		}
+	}
+
+	fn visit_cast(&mut self, value: &Node, target: &Type, _: &Span) {
+		if *target == Type::Float {
+			Self::unsupported("Float");
+		}
+
+		// Bool values are already 0 or 1
+		value.accept(self);
+	}
+
+	fn visit_string(&mut self, _: &str) {
+		Self::unsupported("String");
+	}
+
+	fn visit_array(&mut self, _: &[Node], _: &Span) {
+		Self::unsupported("Array");
+	}
+
+	fn visit_map(&mut self, _: &[Node], _: &[Node], _: &Span) {
+		Self::unsupported("Map");
+	}
+
+	fn visit_none(&mut self) {
+		Self::unsupported("Option");
+	}
+
+	fn visit_some(&mut self, _: &Node) {
+		Self::unsupported("Option");
+	}
+
+	fn visit_ok(&mut self, _: &Node) {
+		Self::unsupported("Result");
+	}
+
+	fn visit_err(&mut self, _: &Node) {
+		Self::unsupported("Result");
+	}
+
+	fn visit_try(&mut self, _: &Node, _: &Span) {
+		Self::unsupported("Result");
+	}
+
+	fn visit_index(&mut self, _: &Node, _: &Node, _: &Span) {
+		Self::unsupported("Array");
+	}
+
+	fn visit_struct_literal(&mut self, _: &str, _: &[String], _: &[Node], _: &Span) {
+		Self::unsupported("Struct");
+	}
+
+	fn visit_field_access(&mut self, _: &Node, _: &str, _: &Span) {
+		Self::unsupported("Struct");
+	}
+
+	fn visit_enum_variant(&mut self, _: &str, _: &str, _: &[Node], _: &Span) {
+		Self::unsupported("Enum");
+	}
+
+	fn visit_match(&mut self, _: &Node, _: &[Pattern], _: &[Node], _: &Span) {
+		Self::unsupported("Match");
+	}
+
+	fn visit_var_assignation(&mut self, name: &str, value: &Node, _: &Span) {
+		value.accept(self);
+
+		match self.scopes.resolve_var(name).copied() {
+			Some(offset) => self.emit(&format!("movq %rax, {}(%rbp)", offset)),
+			None => panic!("Not declared identifier {}", name)
+		}
+	}
+
+	fn visit_element_assignation(&mut self, _: &str, _: &[Accessor], _: &Node, _: &Span) {
+		Self::unsupported("Array or Struct");
+	}
+
+	fn visit_const_declaration(&mut self, name: &str, declared_type: &Type, value: &Node, _: &Span) {
+		if *declared_type == Type::Float {
+			Self::unsupported("Float");
+		}
+
+		// Computed where it is declared, a name used by the initializer may be shadowed where the constant is used
+		match OptimizerVisitor::evaluate(value, &self.constants) {
+			Some(constant) => { self.constants.insert(String::from(name), constant); },
+			None => panic!("Value of constant {} can't be computed at compile time", name)
+		}
+	}
+
+	fn visit_var_declaration(&mut self, name: &str, _: bool, declared_type: &Option<Type>, value: &Node, _: &Span) {
+		if *declared_type == Some(Type::Float) {
+			Self::unsupported("Float");
+		}
+
+		value.accept(self);
+
+		let offset = self.declare_var(name);
+		self.emit(&format!("movq %rax, {}(%rbp)", offset));
+	}
+
+	fn visit_return_statement(&mut self, value: &Option<Node>, _: &Span) {
+		match value {
+			Some(exp) => exp.accept(self),
+			None => self.emit("xorl %eax, %eax")
+		}
+
+		let return_label = self.frame().return_label();
+		self.emit(&format!("jmp {}", return_label));
+	}
+
+	fn visit_if_let(&mut self, _: &Pattern, _: &Node, _: &Option<Node>, _: &Span) {
+		Self::unsupported("Option");
+	}
+
+	fn visit_for_loop(&mut self, _: &str, _: &Node, _: &Option<Node>, _: &Span) {
+		Self::unsupported("Array");
+	}
+
+	fn visit_if_statement(&mut self, condition: &Node, body: &Option<Node>, else_body: &Option<Node>, _: &Span) {
+		let else_label = self.new_label("else");
+		let end_label = self.new_label("endif");
+
+		condition.accept(self);
+		self.emit("cmpq $0, %rax");
+		self.emit(&format!("je {}", else_label));
+
+		if let Some(instruction_list) = body {
+			self.scopes.push_block(Scope::new());
+			instruction_list.accept(self);
+			self.scopes.pop_block();
+		}
+		self.emit(&format!("jmp {}", end_label));
+
+		self.emit_label(&else_label);
+		if let Some(instruction_list) = else_body {
+			self.scopes.push_block(Scope::new());
+			instruction_list.accept(self);
+			self.scopes.pop_block();
+		}
+
+		self.emit_label(&end_label);
+	}
+
+	fn visit_block(&mut self, body: &Option<Node>, value: &Node) {
+		self.scopes.push_block(Scope::new());
+		if let Some(instruction_list) = body {
+			instruction_list.accept(self);
+		}
+		value.accept(self);
+		self.scopes.pop_block();
+	}
+
+	fn visit_if_expression(&mut self, condition: &Node, then_branch: &Node, else_branch: &Node, _: &Span) {
+		let else_label = self.new_label("else");
+		let end_label = self.new_label("endif");
+
+		condition.accept(self);
+		self.emit("cmpq $0, %rax");
+		self.emit(&format!("je {}", else_label));
+
+		then_branch.accept(self);
+		self.emit(&format!("jmp {}", end_label));
+
+		self.emit_label(&else_label);
+		else_branch.accept(self);
+
+		self.emit_label(&end_label);
+	}
+
+	fn visit_instruction_list(&mut self, current: &Node, next: &Option<Node>) {
+		current.accept(self);
+
+		if let Some(x) = next {
+			x.accept(self);
+		}
+	}
+
+	// Every supported value fits in a register, a generic function runs the same code for all its types
+	fn visit_function_declaration(&mut self, name: &str, _: &[TypeParam], param_names: &[String], _: &[Span], param_types: &[Type], _: &Type, body: &Option<Node>, _: &Span) {
+		if param_types.contains(&Type::Float) {
+			Self::unsupported("Float");
+		}
+
+		let label = self.function_label(name);
+		self.scopes.insert_function(name, label.clone());
+
+		self.frames.push(Frame::new(&label));
+		let caller_frame_start = self.scopes.push_frame(Scope::new());
+
+		for (i, param) in param_names.iter().enumerate() {
+			if i < ARG_REGISTERS.len() {
+				let offset = self.declare_var(param);
+				self.emit(&format!("movq {}, {}(%rbp)", ARG_REGISTERS[i], offset));
+			}
+			else {
+				// Pushed by the caller, above the return address and the saved %rbp
+				let offset = 2 * WORD_SIZE + (i - ARG_REGISTERS.len()) as i64 * WORD_SIZE;
+				self.scopes.insert_var(param, offset);
+			}
+		}
+
+		if let Some(body_node) = body {
+			body_node.accept(self);
+		}
+
+		self.scopes.pop_frame(caller_frame_start);
+		let frame = self.frames.pop().expect("No frame");
+		self.assembled_functions.push(frame.assemble());
+	}
+
+	fn visit_function_call(&mut self, name: &str, args: &[Node], _: &Span) {
+		let label = match self.scopes.resolve_function(name) {
+			Some(label) => label.clone(),
+			None => panic!("Undefined function {}", name)
+		};
+
+		let stack_args = args.len().saturating_sub(ARG_REGISTERS.len());
+		let padding = (self.frame().depth + stack_args) % 2;
+		if padding == 1 {
+			self.emit("subq $8, %rsp");
+			self.frame().depth += 1;
+		}
+
+		for arg in args.iter().rev() {
+			arg.accept(self);
+			self.push("%rax");
+		}
+
+		for register in ARG_REGISTERS.iter().take(args.len()) {
+			self.pop(register);
+		}
+
+		self.emit(&format!("call {}", label));
+
+		let cleanup = stack_args + padding;
+		if cleanup > 0 {
+			self.emit(&format!("addq ${}, %rsp", cleanup as i64 * WORD_SIZE));
+			self.frame().depth -= cleanup;
+		}
+	}
+	fn visit_struct_declaration(&mut self, _: &str, _: &[String], _: &[Type], _: &Span) {
+		// Only used by the type checker
+	}
+
+	fn visit_enum_declaration(&mut self, _: &str, _: &[String], _: &[Vec<Type>], _: &Span) {
+		// Only used by the type checker
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::lexer::Lexer;
+	use crate::parser::Parser;
+
+	fn generate(program: &str) -> String {
+		let mut lexer = Lexer::new(program);
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast().unwrap();
+
+		CodeGeneratorVisitor::new().generate(&ast)
+	}
+
+	#[test]
+	fn expression_generation() {
+		let output = generate("let test = 2 * 3 + 4;");
+
+		assert!(output.contains("main:\n"));
+		assert!(output.contains("\timulq %rcx, %rax\n"));
+		assert!(output.contains("\taddq %rcx, %rax\n"));
+		assert!(output.contains("\tmovq %rax, -8(%rbp)\n"));
+	}
+
+	#[test]
+	fn function_generation() {
+		let output = generate("fn add(a: int, b: int) -> int { return a + b; } let result = add(1, 2);");
+
+		assert!(output.contains("jpl_add:\n"));
+		assert!(output.contains("\tmovq %rsi, -16(%rbp)\n"));
+		assert!(output.contains("\tjmp .Lreturn_jpl_add\n"));
+		assert!(output.contains("\tcall jpl_add\n"));
+	}
+
+	#[test]
+	fn constant_generation() {
+		let output = generate("const MAX: int = 7; fn limit() -> int { return MAX; }");
+
+		assert!(output.contains("jpl_limit:\n\tpushq %rbp\n\tmovq %rsp, %rbp\n\tmovq $7, %rax\n"));
+	}
+
+	#[test]
+	fn nested_function_generation() {
+		let output = generate("
+			fn helper() -> int { return 2; }
+			fn outer() -> int {
+				fn helper() -> int { return 1; }
+				return helper();
+			}
+			let value = helper();
+		");
+
+		// The nested `helper` is only visible from `outer`
+		let main = &output[..output.find("\njpl_").unwrap()];
+		assert!(main.contains("\tcall jpl_helper\n"));
+		assert!(output.contains("jpl_outer:\n\tpushq %rbp\n\tmovq %rsp, %rbp\n\tcall jpl_helper_"));
+	}
+
+	#[test]
+	fn runtime_check_generation() {
+		let output = generate("let a = 7; let b = a / 2 + a;");
+
+		assert!(output.contains("\ttestq %rcx, %rcx\n\tjz .Lruntime_error0\n"));
+		assert!(output.contains("\taddq %rcx, %rax\n\tjo .Lruntime_error1\n"));
+		assert!(output.contains(".Lruntime_error_message0:\n\t.ascii \"Runtime error: Division by zero\\n\"\n"));
+		assert!(output.contains(".Lruntime_error_message1:\n\t.ascii \"Runtime error: Integer overflow\\n\"\n"));
+	}
+
+	#[test]
+	#[should_panic]
+	fn float_generation() {
+		generate("let test = 2.5;");
+	}
+
+	// Assemble and run the program with the system toolchain, gives its exit code and error output
+	#[cfg(target_os = "linux")]
+	fn run_native(name: &str, program: &str) -> (Option<i32>, String) {
+		use std::process::Command;
+
+		let directory = std::env::temp_dir().join(format!("jpl_{}_{}", name, std::process::id()));
+		std::fs::create_dir_all(&directory).unwrap();
+		let source = directory.join("program.s");
+		let binary = directory.join("program");
+		std::fs::write(&source, generate(program)).unwrap();
+
+		let compiled = Command::new("cc").arg(&source).arg("-o").arg(&binary).status().expect("No cc to assemble the program");
+		assert!(compiled.success());
+
+		let output = Command::new(&binary).output().unwrap();
+		std::fs::remove_dir_all(&directory).unwrap();
+
+		(output.status.code(), String::from_utf8(output.stderr).unwrap())
+	}
+
+	#[test]
+	#[cfg(target_os = "linux")]
+	#[ignore = "needs cc, run with `cargo test -- --ignored`"]
+	fn native_execution() {
+		let (code, _) = run_native("native_execution", "
+			fn fact(n: int) -> int {
+				if n <= 1 {
+					return 1;
+				} else {
+					return n * fact(n - 1);
+				}
+			}
+
+			fn sum(a: int, b: int, c: int, d: int, e: int, f: int, g: int, h: int) -> int {
+				return a + b + c + d + e + f + g - h;
+			}
+
+			if false && 1 / 0 == 1 {
+				return 1;
+			}
+
+			const BASE: int = 2;
+			const DOUBLE: int = BASE * 2;
+			fn shadowed() -> int {
+				let BASE = 100;
+				return DOUBLE + BASE - BASE;
+			}
+
+			fn helper() -> int { return 2; }
+			fn outer() -> int {
+				fn helper() -> int { return 1; }
+				return helper();
+			}
+
+			let bonus = if fact(3) == 6 { let two = 2; two * 3 } else if true { 1 } else { 0 };
+			let power = (-2) ** 63 / 4611686018427387904;
+
+			return fact(5) - sum(1, 2, 3, 4, 5, 6, 7, 8) + (2 ** 3 ** 2 % 10 << 1 | 1) - 5 + bonus - 6 + shadowed() - 4 + helper() - outer() - 1 + power + 2;
+		");
+
+		assert_eq!(code, Some(120 - 20));
+	}
+
+	#[test]
+	#[cfg(target_os = "linux")]
+	#[ignore = "needs cc, run with `cargo test -- --ignored`"]
+	fn native_runtime_errors() {
+		let programs = [
+			("let big = 9223372036854775807; return big + 1;", "Integer overflow"),
+			("let small = -9223372036854775807; return small - 2;", "Integer overflow"),
+			("let big = 4294967296; return big * big;", "Integer overflow"),
+			("let min = -9223372036854775807 - 1; return min / -1;", "Integer overflow"),
+			("let min = -9223372036854775807 - 1; return -min;", "Integer overflow"),
+			("let zero = 0; return 7 / zero;", "Division by zero"),
+			("let zero = 0; return 7 % zero;", "Division by zero"),
+			("let exponent = -1; return 2 ** exponent;", "Negative exponent"),
+			("return 3 ** 40;", "Integer overflow"),
+			("let amount = 64; return 1 << amount;", "Shift amount out of range"),
+			("let amount = -1; return 1 >> amount;", "Shift amount out of range")
+		];
+
+		for (i, (program, message)) in programs.iter().enumerate() {
+			let (code, error) = run_native(&format!("native_runtime_error{}", i), program);
+
+			assert_eq!(code, Some(1), "{}", program);
+			assert_eq!(error, format!("Runtime error: {}\n", message), "{}", program);
+		}
+	}
+}
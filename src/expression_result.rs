@@ -1,6 +1,62 @@
-#[derive(Clone, Copy, Debug)]
+use std::collections::HashMap;
+use std::fmt;
+
+// The values that can be map keys, floats are left out as they can't be hashed
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MapKey {
+	Int(i64),
+	Bool(bool),
+	String(String)
+}
+
+impl MapKey {
+	pub fn from_result(value: &ExpressionResult) -> Option<MapKey> {
+		match value {
+			ExpressionResult::Int(value) => Some(MapKey::Int(*value)),
+			ExpressionResult::Bool(value) => Some(MapKey::Bool(*value)),
+			ExpressionResult::String(value) => Some(MapKey::String(value.clone())),
+			_ => None
+		}
+	}
+
+	pub fn to_result(&self) -> ExpressionResult {
+		match self {
+			MapKey::Int(value) => ExpressionResult::Int(*value),
+			MapKey::Bool(value) => ExpressionResult::Bool(*value),
+			MapKey::String(value) => ExpressionResult::String(value.clone())
+		}
+	}
+}
+
+impl fmt::Display for MapKey {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			MapKey::Int(value) => write!(f, "{}", value),
+			MapKey::Bool(value) => write!(f, "{}", value),
+			MapKey::String(value) => write!(f, "{:?}", value)
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum ExpressionResult {
 	Int(i64),
 	Float(f64),
-	Bool(bool)
+	Bool(bool),
+	String(String),
+	Array(Vec<ExpressionResult>),
+	Map(HashMap<MapKey, ExpressionResult>),
+	None,
+	Some(Box<ExpressionResult>),
+	Ok(Box<ExpressionResult>),
+	Err(Box<ExpressionResult>),
+	Struct {
+		name: String,
+		fields: HashMap<String, ExpressionResult>
+	},
+	Enum {
+		name: String,
+		variant: String,
+		fields: Vec<ExpressionResult>
+	}
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
+use std::rc::Rc;
+
 use crate::node::Node;
 
 #[derive(Debug, Clone)]
 pub struct Function {
 	pub params: Vec<String>,
-	pub body: Option<Node>
+	pub body: Option<Rc<Node>>
 }
\ No newline at end of file
@@ -1,17 +1,33 @@
-use core::panic;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 use crate::operator::Operator;
-use crate::node::Node;
+use crate::node::{Node, Accessor, Pattern, TypeParam};
 use crate::visitor::{Visitor, Visitable};
-use crate::expression_result::ExpressionResult;
-use crate::scope::Scope;
+use crate::expression_result::{ExpressionResult, MapKey};
+use crate::scope::{Scope, Scopes};
 use crate::function::Function;
 use crate::r#type::Type;
+use crate::span::Span;
+use crate::runtime_error::{RuntimeError, CallFrame};
+use crate::builtin;
 
 pub struct InterpretorVisitor { 
 	result: ExpressionResult,
 	exit_point: bool,
-	scopes: Vec<Scope>,
+	scopes: Scopes<ExpressionResult, Function>,
+	// Callee, arguments and span of the return making the tail call
+	tail_call: Option<(String, Function, Vec<ExpressionResult>, Span)>,
+	// Set when the program failed, unwinds like a return up to `interpret`
+	error: Option<RuntimeError>,
+	// Error returned by `?`, unwinds like an error up to the function call
+	propagated: Option<ExpressionResult>
+}
+
+impl Default for InterpretorVisitor {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl InterpretorVisitor {
@@ -20,127 +36,312 @@ impl InterpretorVisitor {
 		InterpretorVisitor {
 			result: ExpressionResult::Float(0.0),
 			exit_point: false,
-			scopes: Vec::from([ Scope::new() ])
+			scopes: Scopes::new(),
+			tail_call: None,
+			error: None,
+			propagated: None
 		}
 	}
 
-	pub fn interpret(&mut self, ast : Node) {
+	pub fn interpret(&mut self, ast : Node) -> Result<(), RuntimeError> {
 		ast.accept(self);
 
-		for scope in self.scopes.iter() {
-			println!("{:?}", scope.functions);
-			println!("{:?}", scope.memory);
+		match self.error.take() {
+			Some(error) => Err(error),
+			None => Ok(())
 		}
 	}
 
-	fn resolve_scope_var(&mut self, name: &str) -> Option<&mut ExpressionResult> {
-		for scope in self.scopes.iter_mut().rev() {
-			let result = scope.memory.get_mut(name);
+	fn fail(&mut self, message: String, span: Option<Span>) {
+		self.error = Some(RuntimeError::new(message, span));
+	}
 
-			if let Some(_) = result {
-				return result;
+	// The current expression or block is abandoned
+	fn unwinding(&self) -> bool {
+		self.error.is_some() || self.propagated.is_some()
+	}
+
+	fn evaluate_args(&mut self, args: &[Node]) -> Vec<ExpressionResult> {
+		let mut values = Vec::new();
+		for arg in args.iter() {
+			arg.accept(self);
+			if self.unwinding() {
+				break;
 			}
+
+			values.push(self.result.clone());
 		}
 
-		None
+		values
 	}
 
-	fn resolve_scope_function(&self, name: &str) -> Option<&Function> {
-		for scope in self.scopes.iter().rev() {
-			let result = scope.functions.get(name);
+	fn call(&mut self, name: &str, func: &Function, values: Vec<ExpressionResult>, call_site: Span) {
+		let Some(caller_frame_start) = self.enter(name, func, values, call_site) else {
+			return;
+		};
+
+		// The call and its tail calls, innermost last. A frame is left as soon as it doesn't declare the
+		// next callee, it is only kept for the call stack of errors then.
+		let mut frames = Vec::from([(CallFrame { function: String::from(name), call_site }, Some(caller_frame_start))]);
+
+		while let Some((next_name, next_func, next_values, return_span)) = self.tail_call.take() {
+			// The return that made the tail call is done
+			self.exit_point = false;
+
+			// The callee body resolves the functions declared next to it
+			while !self.scopes.is_function_in_frame(&next_name) {
+				let Some(frame_start) = frames.iter_mut().rev().find_map(|(_, frame_start)| frame_start.take()) else {
+					break;
+				};
+
+				self.scopes.pop_frame(frame_start);
+			}
 
-			if let Some(_) = result {
-				return result;
+			// A cycle of tail calls is kept once, deep tail recursion doesn't grow the call stack
+			let frame = CallFrame { function: next_name.clone(), call_site: return_span };
+			let first_left = frames.len() - frames.iter().rev().take_while(|(_, frame_start)| frame_start.is_none()).count();
+			if let Some(position) = frames[first_left..].iter().position(|(previous, _)| *previous == frame) {
+				frames.truncate(first_left + position);
 			}
+
+			match self.enter(&next_name, &next_func, next_values, return_span) {
+				Some(frame_start) => frames.push((frame, Some(frame_start))),
+				None => break
+			}
+		}
+
+		for (frame, frame_start) in frames.into_iter().rev() {
+			self.leave(frame, frame_start);
+		}
+	}
+
+	// Runs the body in a new frame, the frame to give to `leave` is returned
+	fn enter(&mut self, name: &str, func: &Function, values: Vec<ExpressionResult>, call_site: Span) -> Option<usize> {
+		if func.params.len() != values.len() {
+			self.fail(format!("Wrong number of args to call {}", name), Some(call_site));
+			return None;
+		}
+
+		let caller_frame_start = self.scopes.push_frame(Scope::with_variables(func.params.iter().cloned().zip(values)));
+		if let Some(body) = &func.body {
+			body.accept(self);
 		}
 
-		None
+		Some(caller_frame_start)
 	}
 
-	fn insert_var(&mut self, name: &str, value: ExpressionResult) {
-		let current = self.scopes.last_mut();
+	// `caller_frame_start` is None when the frame was already left for a tail call
+	fn leave(&mut self, frame: CallFrame, caller_frame_start: Option<usize>) {
+		if let Some(caller_frame_start) = caller_frame_start {
+			self.scopes.pop_frame(caller_frame_start);
+		}
 
-		if let Some(scope) = current {
-			scope.memory.insert(String::from(name), value);
+		if let Some(propagated) = self.propagated.take() {
+			self.result = propagated;
 		}
-		else {
-			panic!("No scope");
+
+		if let Some(error) = &mut self.error {
+			error.call_stack.push(frame);
 		}
-		
+
+		// The function exited, no more exit_point triggered
+		self.exit_point = false;
 	}
 
-	fn insert_function(&mut self, name: &str, value: Function) {
-		let current = self.scopes.last_mut();
+	fn call_builtin(&mut self, name: &str, args: &[Node], span: &Span) {
+		let values = self.evaluate_args(args);
+		if self.unwinding() {
+			return;
+		}
 
-		if let Some(scope) = current {
-			scope.functions.insert(String::from(name), value);
+		match builtin::call(name, &values) {
+			Ok(value) => self.result = value,
+			Err(message) => self.fail(message, Some(*span))
 		}
-		else {
-			panic!("No scope");
+	}
+
+	fn element_position(len: usize, index: i64) -> Result<usize, String> {
+		match usize::try_from(index) {
+			Ok(position) if position < len => Ok(position),
+			_ => Err(format!("Index {} out of bounds for length {}", index, len))
 		}
-		
 	}
 
-	fn apply_binary_op_float(op: &Operator, lhs: f64, rhs: f64) -> ExpressionResult {
+	// `indexes` holds the evaluated `Accessor::Index` of `path`, in order
+	fn assign_element(target: &mut ExpressionResult, path: &[Accessor], indexes: &[ExpressionResult], value: ExpressionResult) -> Result<(), String> {
+		let mut target = target;
+		let mut indexes = indexes.iter();
+		for (i, accessor) in path.iter().enumerate() {
+			target = match (target, accessor) {
+				(ExpressionResult::Map(entries), Accessor::Index(_)) => {
+					let key = indexes.next().and_then(MapKey::from_result).ok_or(String::from("Wrong type"))?;
+
+					// Assigning the last key inserts it
+					if i + 1 == path.len() {
+						entries.insert(key, value);
+						return Ok(());
+					}
+
+					match entries.get_mut(&key) {
+						Some(entry) => entry,
+						None => return Err(format!("Key {} not found", key))
+					}
+				},
+				(ExpressionResult::Array(elements), Accessor::Index(_)) => match indexes.next() {
+					Some(ExpressionResult::Int(index)) => {
+						let position = Self::element_position(elements.len(), *index)?;
+						&mut elements[position]
+					},
+					_ => return Err(String::from("Wrong type"))
+				},
+				(ExpressionResult::Struct { fields, .. }, Accessor::Field(field)) => match fields.get_mut(field) {
+					Some(element) => element,
+					None => return Err(format!("No field {}", field))
+				},
+				_ => return Err(String::from("Wrong type"))
+			};
+		}
+
+		*target = value;
+
+		Ok(())
+	}
+
+	// Collect the values bound by `pattern` when it matches `value`
+	fn match_pattern(pattern: &Pattern, value: &ExpressionResult, bindings: &mut Vec<(String, ExpressionResult)>) -> bool {
+		match (pattern, value) {
+			(Pattern::Wildcard, _) => true,
+			(Pattern::Binding(name), _) => {
+				bindings.push((name.clone(), value.clone()));
+				true
+			},
+			(Pattern::Int(expected), ExpressionResult::Int(value)) => expected == value,
+			(Pattern::Bool(expected), ExpressionResult::Bool(value)) => expected == value,
+			(Pattern::None, ExpressionResult::None) => true,
+			(Pattern::Some(pattern), ExpressionResult::Some(value)) | (Pattern::Ok(pattern), ExpressionResult::Ok(value)) |
+			(Pattern::Err(pattern), ExpressionResult::Err(value)) => Self::match_pattern(pattern, value, bindings),
+			(Pattern::Variant { variant: expected, fields: patterns, .. }, ExpressionResult::Enum { variant, fields, .. }) => {
+				expected == variant && patterns.len() == fields.len()
+					&& patterns.iter().zip(fields.iter()).all(|(pattern, field)| Self::match_pattern(pattern, field, bindings))
+			},
+			_ => false
+		}
+	}
+
+	fn apply_binary_op_float(op: &Operator, lhs: f64, rhs: f64) -> Result<ExpressionResult, String> {
 		match op {
-			Operator::Add => ExpressionResult::Float(lhs + rhs),
-			Operator::Minus => ExpressionResult::Float(lhs - rhs),
-			Operator::Product =>  ExpressionResult::Float(lhs * rhs),
-			Operator::Divide => ExpressionResult::Float(lhs / rhs),
-			Operator::LowerOrEq => ExpressionResult::Bool(lhs <= rhs),
-			Operator::GreaterOrEq => ExpressionResult::Bool(lhs >= rhs),
-			Operator::Equal => ExpressionResult::Bool(lhs == rhs),
-			Operator::NotEqual => ExpressionResult::Bool(lhs != rhs),
-			Operator::Lower => ExpressionResult::Bool(lhs < rhs),
-			Operator::Greater => ExpressionResult::Bool(lhs > rhs),
-			_ => panic!("Wrong op")
+			Operator::Add => Ok(ExpressionResult::Float(lhs + rhs)),
+			Operator::Minus => Ok(ExpressionResult::Float(lhs - rhs)),
+			Operator::Product => Ok(ExpressionResult::Float(lhs * rhs)),
+			Operator::Divide => Ok(ExpressionResult::Float(lhs / rhs)),
+			Operator::Modulo => Ok(ExpressionResult::Float(lhs % rhs)),
+			Operator::Power => Ok(ExpressionResult::Float(lhs.powf(rhs))),
+			Operator::LowerOrEq => Ok(ExpressionResult::Bool(lhs <= rhs)),
+			Operator::GreaterOrEq => Ok(ExpressionResult::Bool(lhs >= rhs)),
+			Operator::Equal => Ok(ExpressionResult::Bool(lhs == rhs)),
+			Operator::NotEqual => Ok(ExpressionResult::Bool(lhs != rhs)),
+			Operator::Lower => Ok(ExpressionResult::Bool(lhs < rhs)),
+			Operator::Greater => Ok(ExpressionResult::Bool(lhs > rhs)),
+			_ => Err(String::from("Wrong op"))
 		}
 	}
 
-	fn apply_binary_op_int(op: &Operator, lhs: i64, rhs: i64) -> ExpressionResult {
+	fn apply_binary_op_int(op: &Operator, lhs: i64, rhs: i64) -> Result<ExpressionResult, String> {
 		match op {
-			Operator::Add => ExpressionResult::Int(lhs + rhs),
-			Operator::Minus => ExpressionResult::Int(lhs - rhs),
-			Operator::Product => ExpressionResult::Int(lhs * rhs),
-			Operator::Divide => ExpressionResult::Int(lhs / rhs),
-			Operator::LowerOrEq => ExpressionResult::Bool(lhs <= rhs),
-			Operator::GreaterOrEq => ExpressionResult::Bool(lhs >= rhs),
-			Operator::Equal => ExpressionResult::Bool(lhs == rhs),
-			Operator::NotEqual => ExpressionResult::Bool(lhs != rhs),
-			Operator::Lower => ExpressionResult::Bool(lhs < rhs),
-			Operator::Greater => ExpressionResult::Bool(lhs > rhs),
-			_ => panic!("Wrong op")
+			Operator::Add => lhs.checked_add(rhs).map(ExpressionResult::Int).ok_or(String::from("Integer overflow")),
+			Operator::Minus => lhs.checked_sub(rhs).map(ExpressionResult::Int).ok_or(String::from("Integer overflow")),
+			Operator::Product => lhs.checked_mul(rhs).map(ExpressionResult::Int).ok_or(String::from("Integer overflow")),
+			Operator::Divide => {
+				if rhs == 0 {
+					return Err(String::from("Division by zero"));
+				}
+
+				lhs.checked_div(rhs).map(ExpressionResult::Int).ok_or(String::from("Integer overflow"))
+			},
+			Operator::Modulo => {
+				if rhs == 0 {
+					return Err(String::from("Division by zero"));
+				}
+
+				lhs.checked_rem(rhs).map(ExpressionResult::Int).ok_or(String::from("Integer overflow"))
+			},
+			Operator::Power => {
+				if rhs < 0 {
+					return Err(String::from("Negative exponent"));
+				}
+
+				u32::try_from(rhs).ok()
+					.and_then(|exponent| lhs.checked_pow(exponent))
+					.map(ExpressionResult::Int)
+					.ok_or(String::from("Integer overflow"))
+			},
+			Operator::BitwiseAnd => Ok(ExpressionResult::Int(lhs & rhs)),
+			Operator::BitwiseOr => Ok(ExpressionResult::Int(lhs | rhs)),
+			Operator::BitwiseXor => Ok(ExpressionResult::Int(lhs ^ rhs)),
+			Operator::ShiftLeft | Operator::ShiftRight => {
+				if !(0..64).contains(&rhs) {
+					return Err(String::from("Shift amount out of range"));
+				}
+
+				if *op == Operator::ShiftLeft {
+					Ok(ExpressionResult::Int(lhs << rhs))
+				}
+				else {
+					Ok(ExpressionResult::Int(lhs >> rhs))
+				}
+			},
+			Operator::LowerOrEq => Ok(ExpressionResult::Bool(lhs <= rhs)),
+			Operator::GreaterOrEq => Ok(ExpressionResult::Bool(lhs >= rhs)),
+			Operator::Equal => Ok(ExpressionResult::Bool(lhs == rhs)),
+			Operator::NotEqual => Ok(ExpressionResult::Bool(lhs != rhs)),
+			Operator::Lower => Ok(ExpressionResult::Bool(lhs < rhs)),
+			Operator::Greater => Ok(ExpressionResult::Bool(lhs > rhs)),
+			_ => Err(String::from("Wrong op"))
 		}
 	}
 
-	fn apply_binary_op_bool(op: &Operator, lhs: bool, rhs: bool) -> ExpressionResult {
+	fn apply_binary_op_bool(op: &Operator, lhs: bool, rhs: bool) -> Result<ExpressionResult, String> {
 		match op {
-			Operator::LogicalAnd => ExpressionResult::Bool(lhs && rhs),
-			Operator::LogicalOr => ExpressionResult::Bool(lhs || rhs),
-			Operator::Equal => ExpressionResult::Bool(lhs == rhs),
-			Operator::NotEqual => ExpressionResult::Bool(lhs != rhs),
-			_ => panic!("Wrong op")
+			Operator::LogicalAnd => Ok(ExpressionResult::Bool(lhs && rhs)),
+			Operator::LogicalOr => Ok(ExpressionResult::Bool(lhs || rhs)),
+			Operator::Equal => Ok(ExpressionResult::Bool(lhs == rhs)),
+			Operator::NotEqual => Ok(ExpressionResult::Bool(lhs != rhs)),
+			_ => Err(String::from("Wrong op"))
 		}
 	}
 
-	fn apply_unary_op_bool(op: &Operator, rhs: bool) -> ExpressionResult{
+	fn apply_binary_op_string(op: &Operator, lhs: String, rhs: String) -> Result<ExpressionResult, String> {
 		match op {
-			Operator::Not => ExpressionResult::Bool(!rhs),
-			_ => panic!("No valid opertaor for bool")
+			Operator::Add => Ok(ExpressionResult::String(lhs + &rhs)),
+			Operator::LowerOrEq => Ok(ExpressionResult::Bool(lhs <= rhs)),
+			Operator::GreaterOrEq => Ok(ExpressionResult::Bool(lhs >= rhs)),
+			Operator::Equal => Ok(ExpressionResult::Bool(lhs == rhs)),
+			Operator::NotEqual => Ok(ExpressionResult::Bool(lhs != rhs)),
+			Operator::Lower => Ok(ExpressionResult::Bool(lhs < rhs)),
+			Operator::Greater => Ok(ExpressionResult::Bool(lhs > rhs)),
+			_ => Err(String::from("Wrong op"))
 		}
 	}
 
-	fn apply_unary_op_int(op: &Operator, rhs: i64) -> ExpressionResult {
+	fn apply_unary_op_bool(op: &Operator, rhs: bool) -> Result<ExpressionResult, String> {
 		match op {
-			Operator::Minus => ExpressionResult::Int(-rhs),
-			_ => panic!("No valid opertaor for int")
+			Operator::Not => Ok(ExpressionResult::Bool(!rhs)),
+			_ => Err(String::from("No valid opertaor for bool"))
 		}
 	}
 
-	fn apply_unary_op_float(op: &Operator, rhs: f64) -> ExpressionResult {
+	fn apply_unary_op_int(op: &Operator, rhs: i64) -> Result<ExpressionResult, String> {
 		match op {
-			Operator::Minus => ExpressionResult::Float(-rhs),
-			_ => panic!("No valid opertaor for float")
+			Operator::Minus => rhs.checked_neg().map(ExpressionResult::Int).ok_or(String::from("Integer overflow")),
+			Operator::BitwiseNot => Ok(ExpressionResult::Int(!rhs)),
+			_ => Err(String::from("No valid opertaor for int"))
+		}
+	}
+
+	fn apply_unary_op_float(op: &Operator, rhs: f64) -> Result<ExpressionResult, String> {
+		match op {
+			Operator::Minus => Ok(ExpressionResult::Float(-rhs)),
+			_ => Err(String::from("No valid opertaor for float"))
 		}
 	}
 }
@@ -158,111 +359,463 @@ impl Visitor for InterpretorVisitor {
 		self.result = ExpressionResult::Bool(value);
 	}
 
-	fn visit_identifier(&mut self, name: &String) {
-		let result = self.resolve_scope_var(name);
+	fn visit_string(&mut self, value: &str) {
+		self.result = ExpressionResult::String(String::from(value));
+	}
+
+	fn visit_identifier(&mut self, name: &str, span: &Span) {
+		let result = self.scopes.resolve_var_mut(name);
 		match result {
-			Some(x) => { self.result = *x },
-			None => panic!("Identifier not declared")
+			Some(x) => { self.result = x.clone() },
+			None => self.fail(format!("Identifier {} not declared", name), Some(*span))
 		}
 	}
 
-	fn visit_binary_op(&mut self, op: &Operator, left: &Node, right: &Node) {
+	fn visit_binary_op(&mut self, op: &Operator, left: &Node, right: &Node, span: &Span) {
 		left.accept(self);
-		let left_result = self.result;
+		if self.unwinding() {
+			return;
+		}
+		let left_result = self.result.clone();
+
+		// Short-circuit, the right operand is not evaluated when the left one decides
+		if let ExpressionResult::Bool(lhs) = &left_result {
+			if (*op == Operator::LogicalAnd && !lhs) || (*op == Operator::LogicalOr && *lhs) {
+				return;
+			}
+		}
 
 		right.accept(self);
-		let right_result = self.result;
+		if self.unwinding() {
+			return;
+		}
+		let right_result = self.result.clone();
 		
-		match left_result {
-			ExpressionResult::Int(lhs) => {
-				if let ExpressionResult::Int(rhs) = right_result {
-					self.result = Self::apply_binary_op_int(op, lhs, rhs);
-				}
-				else {
-					panic!("Wrong type") // TODO: Type checking
-				}
-			},
-			ExpressionResult::Float(lhs) => {
-				if let ExpressionResult::Float(rhs) = right_result {
-					self.result = Self::apply_binary_op_float(op, lhs, rhs);
+		let result = match (left_result, right_result) {
+			(ExpressionResult::Int(lhs), ExpressionResult::Int(rhs)) => Self::apply_binary_op_int(op, lhs, rhs),
+			(ExpressionResult::Float(lhs), ExpressionResult::Float(rhs)) => Self::apply_binary_op_float(op, lhs, rhs),
+			(ExpressionResult::Bool(lhs), ExpressionResult::Bool(rhs)) => Self::apply_binary_op_bool(op, lhs, rhs),
+			(ExpressionResult::String(lhs), ExpressionResult::String(rhs)) => Self::apply_binary_op_string(op, lhs, rhs),
+			_ => Err(String::from("Wrong type"))
+		};
+
+		match result {
+			Ok(value) => self.result = value,
+			Err(message) => self.fail(message, Some(*span))
+		}
+	}
+
+	fn visit_unary_op(&mut self, op: &Operator, right: &Node, span: &Span) {
+		right.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		let result = match self.result {
+			ExpressionResult::Bool(rhs) => Self::apply_unary_op_bool(op, rhs),
+			ExpressionResult::Int(rhs) => Self::apply_unary_op_int(op, rhs),
+			ExpressionResult::Float(rhs) => Self::apply_unary_op_float(op, rhs),
+			_ => Err(String::from("Wrong type"))
+		};
+
+		match result {
+			Ok(value) => self.result = value,
+			Err(message) => self.fail(message, Some(*span))
+		}
+	}
+
+	fn visit_cast(&mut self, value: &Node, target: &Type, span: &Span) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		let result = match (&self.result, target) {
+			(ExpressionResult::Int(value), Type::Float) => Ok(ExpressionResult::Float(*value as f64)),
+			(ExpressionResult::Float(value), Type::Int) => {
+				// Truncate toward zero, values without an int equivalent are errors
+				if value.is_nan() || *value < i64::MIN as f64 || *value >= i64::MAX as f64 {
+					Err(format!("Can't cast {} as int", value))
 				}
 				else {
-					panic!("Wrong type") // TODO: Type checking
+					Ok(ExpressionResult::Int(*value as i64))
 				}
 			},
-			ExpressionResult::Bool(lhs) => {
-				if let ExpressionResult::Bool(rhs) = right_result {
-					self.result = Self::apply_binary_op_bool(op, lhs, rhs);
-				}
-				else {
-					panic!("Wrong type") // TODO: Type checking
+			(ExpressionResult::Bool(value), Type::Int) => Ok(ExpressionResult::Int(*value as i64)),
+			(ExpressionResult::Int(_), Type::Int) | (ExpressionResult::Float(_), Type::Float) | (ExpressionResult::Bool(_), Type::Bool) |
+			(ExpressionResult::String(_), Type::String) | (ExpressionResult::Array(_), Type::Array(_)) | (ExpressionResult::Map(_), Type::Map(_, _)) | (ExpressionResult::Struct { .. }, Type::Named(_)) |
+			(ExpressionResult::Enum { .. }, Type::Named(_)) | (ExpressionResult::None | ExpressionResult::Some(_), Type::Option(_)) |
+			(ExpressionResult::Ok(_) | ExpressionResult::Err(_), Type::Result(_, _)) => Ok(self.result.clone()),
+			(_, target) => Err(format!("Can't cast as {:?}", target))
+		};
+
+		match result {
+			Ok(value) => self.result = value,
+			Err(message) => self.fail(message, Some(*span))
+		}
+	}
+
+	fn visit_array(&mut self, elements: &[Node], _: &Span) {
+		let values = self.evaluate_args(elements);
+		if self.unwinding() {
+			return;
+		}
+
+		self.result = ExpressionResult::Array(values);
+	}
+
+	fn visit_none(&mut self) {
+		self.result = ExpressionResult::None;
+	}
+
+	fn visit_some(&mut self, value: &Node) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		self.result = ExpressionResult::Some(Box::new(self.result.clone()));
+	}
+
+	fn visit_ok(&mut self, value: &Node) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		self.result = ExpressionResult::Ok(Box::new(self.result.clone()));
+	}
+
+	fn visit_err(&mut self, value: &Node) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		self.result = ExpressionResult::Err(Box::new(self.result.clone()));
+	}
+
+	fn visit_try(&mut self, value: &Node, span: &Span) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		match &self.result {
+			ExpressionResult::Ok(value) => self.result = *value.clone(),
+			ExpressionResult::Err(_) => self.propagated = Some(self.result.clone()),
+			_ => self.fail(String::from("Wrong type"), Some(*span))
+		}
+	}
+
+	fn visit_map(&mut self, keys: &[Node], values: &[Node], span: &Span) {
+		let keys = self.evaluate_args(keys);
+		if self.unwinding() {
+			return;
+		}
+
+		let values = self.evaluate_args(values);
+		if self.unwinding() {
+			return;
+		}
+
+		let mut entries = HashMap::new();
+		for (key, value) in keys.iter().zip(values) {
+			match MapKey::from_result(key) {
+				Some(key) => { entries.insert(key, value); },
+				None => {
+					self.fail(String::from("Wrong type"), Some(*span));
+					return;
 				}
 			}
 		}
+
+		self.result = ExpressionResult::Map(entries);
 	}
 
-	fn visit_unary_op(&mut self, op: &Operator, right: &Node) {
-		right.accept(self);
+	fn visit_struct_literal(&mut self, name: &str, field_names: &[String], values: &[Node], _: &Span) {
+		let values = self.evaluate_args(values);
+		if self.unwinding() {
+			return;
+		}
 
-		match self.result {
-			ExpressionResult::Bool(rhs) => {
-				self.result = Self::apply_unary_op_bool(op, rhs);
+		self.result = ExpressionResult::Struct {
+			name: String::from(name),
+			fields: field_names.iter().cloned().zip(values).collect()
+		};
+	}
+
+	fn visit_enum_variant(&mut self, enum_name: &str, variant: &str, args: &[Node], _: &Span) {
+		let values = self.evaluate_args(args);
+		if self.unwinding() {
+			return;
+		}
+
+		self.result = ExpressionResult::Enum {
+			name: String::from(enum_name),
+			variant: String::from(variant),
+			fields: values
+		};
+	}
+
+	fn visit_match(&mut self, value: &Node, patterns: &[Pattern], arms: &[Node], span: &Span) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+		let value = self.result.clone();
+
+		for (pattern, arm) in patterns.iter().zip(arms) {
+			let mut bindings = Vec::new();
+			if !Self::match_pattern(pattern, &value, &mut bindings) {
+				continue;
+			}
+
+			self.scopes.push_block(Scope::with_variables(bindings));
+			arm.accept(self);
+			self.scopes.pop_block();
+
+			return;
+		}
+
+		self.fail(String::from("No match arm for the value"), Some(*span));
+	}
+
+	fn visit_field_access(&mut self, value: &Node, field: &str, span: &Span) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		let result = match &mut self.result {
+			ExpressionResult::Struct { fields, .. } => fields.remove(field).ok_or(format!("No field {}", field)),
+			_ => Err(String::from("Wrong type"))
+		};
+
+		match result {
+			Ok(value) => self.result = value,
+			Err(message) => self.fail(message, Some(*span))
+		}
+	}
+
+	fn visit_index(&mut self, array: &Node, index: &Node, span: &Span) {
+		array.accept(self);
+		if self.unwinding() {
+			return;
+		}
+		let array_result = self.result.clone();
+
+		index.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		let result = match (array_result, &self.result) {
+			(ExpressionResult::Array(mut elements), ExpressionResult::Int(index)) => {
+				Self::element_position(elements.len(), *index).map(|position| elements.swap_remove(position))
 			},
-			ExpressionResult::Int(rhs) => {
-				self.result = Self::apply_unary_op_int(op, rhs);
+			(ExpressionResult::Map(mut entries), key) => match MapKey::from_result(key) {
+				Some(key) => entries.remove(&key).ok_or(format!("Key {} not found", key)),
+				None => Err(String::from("Wrong type"))
 			},
-			ExpressionResult::Float(rhs) => {
-				self.result = Self::apply_unary_op_float(op, rhs);
+			_ => Err(String::from("Wrong type"))
+		};
+
+		match result {
+			Ok(value) => self.result = value,
+			Err(message) => self.fail(message, Some(*span))
+		}
+	}
+
+	fn visit_element_assignation(&mut self, name: &str, path: &[Accessor], value: &Node, span: &Span) {
+		let mut index_values = Vec::new();
+		for accessor in path.iter() {
+			if let Accessor::Index(index) = accessor {
+				index.accept(self);
+				if self.unwinding() {
+					return;
+				}
+
+				index_values.push(self.result.clone());
 			}
 		}
+
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+		let result = self.result.clone();
+
+		let assigned = match self.scopes.resolve_var_mut(name) {
+			Some(var) => Self::assign_element(var, path, &index_values, result),
+			None => Err(format!("Identifier {} not declared", name))
+		};
+
+		if let Err(message) = assigned {
+			self.fail(message, Some(*span));
+		}
 	}
 
-	fn visit_var_declaration(&mut self, name: &String, _: &Option<Type>, value: &Node) {
+	fn visit_const_declaration(&mut self, name: &str, _: &Type, value: &Node, _: &Span) {
 		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
 
-		self.insert_var(name, self.result);
+		self.scopes.insert_constant(name, self.result.clone());
 	}
 
-	fn visit_var_assignation(&mut self, name: &String, value: &Node) {
+	fn visit_var_declaration(&mut self, name: &str, _: bool, _: &Option<Type>, value: &Node, _: &Span) {
 		value.accept(self);
-		let result = self.result;
+		if self.unwinding() {
+			return;
+		}
+
+		self.scopes.insert_var(name, self.result.clone());
+	}
+
+	fn visit_var_assignation(&mut self, name: &str, value: &Node, span: &Span) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+		let result = self.result.clone();
 
-		match self.resolve_scope_var(name) {
+		match self.scopes.resolve_var_mut(name) {
 			Some(var) => {
 				*var = result;
 			},
-			None => panic!("Not declared identifier {}", name)
+			None => self.fail(format!("Identifier {} not declared", name), Some(*span))
 		}
 	}
 
-	fn visit_return_statement(&mut self, value: &Option<Node>) {
-		if let Some(exp) = value {
-			exp.accept(self);
+	fn visit_return_statement(&mut self, value: &Option<Node>, span: &Span) {
+		match value {
+			// Tail call, executed by the caller once this body is done
+			Some(Node::FunctionCall { name, args, .. }) if self.scopes.in_function() && self.scopes.resolve_function(name).is_some() => {
+				let func = self.scopes.resolve_function(name).cloned().expect("Function not declared");
+				let values = self.evaluate_args(args);
+
+				if !self.unwinding() {
+					self.tail_call = Some((name.clone(), func, values, *span));
+				}
+			},
+			Some(exp) => exp.accept(self),
+			None => {}
 		}
 
 		self.exit_point = true;
 	}
 
-	fn visit_if_statement(&mut self, condition: &Node, body: &Option<Node>) {
+	fn visit_if_statement(&mut self, condition: &Node, body: &Option<Node>, else_body: &Option<Node>, span: &Span) {
+		if body.is_none() && else_body.is_none() {
+			return;
+		}
+
+		condition.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		let taken = match self.result {
+			ExpressionResult::Bool(true) => body,
+			ExpressionResult::Bool(false) => else_body,
+			_ => {
+				self.fail(String::from("Except bool value as condition"), Some(*span));
+				return;
+			}
+		};
+
+		if let Some(instruction_list) = taken {
+			self.scopes.push_block(Scope::new());
+			instruction_list.accept(self);
+			self.scopes.pop_block();
+		}
+	}
+
+	fn visit_if_let(&mut self, pattern: &Pattern, value: &Node, body: &Option<Node>, _: &Span) {
+		value.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		let mut bindings = Vec::new();
+		if !Self::match_pattern(pattern, &self.result, &mut bindings) {
+			return;
+		}
+
 		if let Some(instruction_list) = body {
-			condition.accept(self);
-			if let ExpressionResult::Bool(result) = self.result {
-				if result {
-					self.scopes.push(Scope::new());
-					instruction_list.accept(self);
-					self.scopes.pop();
-				}
+			self.scopes.push_block(Scope::with_variables(bindings));
+			instruction_list.accept(self);
+			self.scopes.pop_block();
+		}
+	}
+
+	fn visit_for_loop(&mut self, variable: &str, iterable: &Node, body: &Option<Node>, span: &Span) {
+		iterable.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		// Arrays and maps are values, assigning the iterated value in the body doesn't change the elements visited
+		let elements = match &self.result {
+			ExpressionResult::Array(elements) => elements.clone(),
+			// The keys, sorted as the map has no order
+			ExpressionResult::Map(entries) => {
+				let mut keys: Vec<&MapKey> = entries.keys().collect();
+				keys.sort();
+
+				keys.into_iter().map(MapKey::to_result).collect()
+			},
+			_ => {
+				self.fail(String::from("Wrong type"), Some(*span));
+				return;
 			}
-			else {
-				panic!("Except bool value as condition");
+		};
+
+		let Some(instruction_list) = body else {
+			return;
+		};
+
+		for element in elements {
+			self.scopes.push_block(Scope::with_variables([(String::from(variable), element)]));
+			instruction_list.accept(self);
+			self.scopes.pop_block();
+
+			if self.exit_point || self.unwinding() {
+				break;
 			}
 		}
 	}
 
+	fn visit_block(&mut self, body: &Option<Node>, value: &Node) {
+		self.scopes.push_block(Scope::new());
+		if let Some(instruction_list) = body {
+			instruction_list.accept(self);
+		}
+		if !self.unwinding() {
+			value.accept(self);
+		}
+		self.scopes.pop_block();
+	}
+
+	fn visit_if_expression(&mut self, condition: &Node, then_branch: &Node, else_branch: &Node, span: &Span) {
+		condition.accept(self);
+		if self.unwinding() {
+			return;
+		}
+
+		match self.result {
+			ExpressionResult::Bool(true) => then_branch.accept(self),
+			ExpressionResult::Bool(false) => else_branch.accept(self),
+			_ => self.fail(String::from("Except bool value as condition"), Some(*span))
+		}
+	}
+
 	fn visit_instruction_list(&mut self, current: &Node, next: &Option<Node>) {
 		current.accept(self);
 
-		if self.exit_point {
+		if self.exit_point || self.unwinding() {
 			// We stop the current block
 			return;
 		}
@@ -272,46 +825,594 @@ impl Visitor for InterpretorVisitor {
 		}
 	}
 
-	fn visit_function_declaration(&mut self, name: &String, param_names: &Vec<String>, _: &Vec<Type>, _: &Type, body: &Option<Node>) {
-		self.insert_function(name, Function {
-			params: param_names.clone(),
-			body: body.clone(),
+	fn visit_function_declaration(&mut self, name: &str, _: &[TypeParam], param_names: &[String], _: &[Span], _: &[Type], _: &Type, body: &Option<Node>, _: &Span) {
+		self.scopes.insert_function(name, Function {
+			params: param_names.to_vec(),
+			body: body.clone().map(Rc::new),
 		});
 	}
 
-	fn visit_function_call(&mut self, name: &String, args: &Vec<Node>) {
-		let result = self.resolve_scope_function(name);
-		match result {
-			Some(func) => {
-				if func.params.len() != args.len() {
-					panic!("Wrong number of args to call {}", name);
+	fn visit_function_call(&mut self, name: &str, args: &[Node], span: &Span) {
+		let func = match self.scopes.resolve_function(name) {
+			Some(func) => func.clone(),
+			None if builtin::is_builtin(name) => {
+				self.call_builtin(name, args, span);
+				return;
+			},
+			None => {
+				self.fail(format!("Function {} not declared", name), Some(*span));
+				return;
+			}
+		};
+		let values = self.evaluate_args(args);
+		if self.unwinding() {
+			return;
+		}
+
+		self.call(name, &func, values, *span);
+	}
+
+	fn visit_struct_declaration(&mut self, _: &str, _: &[String], _: &[Type], _: &Span) {
+		// Only used by the type checker
+	}
+
+	fn visit_enum_declaration(&mut self, _: &str, _: &[String], _: &[Vec<Type>], _: &Span) {
+		// Only used by the type checker
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::lexer::Lexer;
+	use crate::parser::Parser;
+
+	fn interpret(program: &str) -> InterpretorVisitor {
+		let mut lexer = Lexer::new(program);
+		let mut parser = Parser::new(&mut lexer);
+
+		let mut interpretor = InterpretorVisitor::new();
+		interpretor.interpret(parser.ast().unwrap()).unwrap();
+
+		interpretor
+	}
+
+	#[test]
+	fn if_block_scoping() {
+		let mut interpretor = interpret("
+			let x = 1;
+			let mut assigned = 1;
+			if true {
+				let x = 2;
+				let inner = x;
+				assigned = x;
+			}
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("x"), Some(&mut ExpressionResult::Int(1)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("assigned"), Some(&mut ExpressionResult::Int(2)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("inner"), None);
+	}
+
+	#[test]
+	fn recursive_call() {
+		let mut interpretor = interpret("
+			fn fact(n: int) -> int {
+				if n <= 1 {
+					return 1;
+				}
+				return n * fact(n - 1);
+			}
+			let result = fact(10);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("result"), Some(&mut ExpressionResult::Int(3628800)));
+	}
+
+	#[test]
+	fn tail_call() {
+		let mut interpretor = interpret("
+			fn count(n: int, max: int) -> int {
+				if n == max {
+					return n;
 				}
+				return count(n + 1, max);
+			}
+			let result = count(0, 1000000);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("result"), Some(&mut ExpressionResult::Int(1000000)));
+	}
 
-				match func.body.clone() {
-					Some(body) => {
-						let mut function_scope = Scope::new();
+	#[test]
+	fn nested_tail_call() {
+		let mut interpretor = interpret("
+			fn outer(n: int) -> int {
+				fn helper(k: int) -> int {
+					if k == 0 {
+						return 0;
+					}
+					return helper(k - 1);
+				}
+				return helper(n);
+			}
+			let result = outer(3);
+		");
 
-						let func_params = func.params.clone();
-						for (i, arg) in args.iter().enumerate() {
-							arg.accept(self);
+		assert_eq!(interpretor.scopes.resolve_var_mut("result"), Some(&mut ExpressionResult::Int(0)));
+	}
 
-							function_scope.memory.insert(func_params[i].clone(), self.result.clone());
+	#[test]
+	fn tail_call_to_function_declared_by_tail_call() {
+		let mut interpretor = interpret("
+			fn a(n: int) -> int {
+				fn b(k: int) -> int {
+					fn c(j: int) -> int {
+						if j == 0 {
+							return 0;
 						}
+						return 1 + c(j - 1);
+					}
+					return c(k);
+				}
+				return b(n);
+			}
+			let result = a(3);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("result"), Some(&mut ExpressionResult::Int(3)));
+	}
 
-						let caller_scopes = self.scopes.clone();
-						self.scopes = Vec::from([function_scope]);
+	#[test]
+	fn runtime_error_call_stack() {
+		let mut lexer = Lexer::new("fn div(a: int, b: int) -> int {\n\treturn a / b;\n}\nlet result = div(1, 0);");
+		let mut parser = Parser::new(&mut lexer);
 
-						body.accept(self);
+		let error = InterpretorVisitor::new().interpret(parser.ast().unwrap()).unwrap_err();
 
-						self.scopes = caller_scopes;
-					},
-					None => {}
+		assert_eq!(error.message, "Division by zero");
+		assert_eq!(error.span, Some(Span::new(1, 10)));
+		assert_eq!(error.call_stack, Vec::from([CallFrame { function: String::from("div"), call_site: Span::new(3, 13) }]));
+	}
+
+	#[test]
+	fn tail_call_stack() {
+		let mut lexer = Lexer::new("fn first(n: int) -> int {\n\treturn second(n);\n}\nfn second(n: int) -> int {\n\treturn third(n);\n}\nfn third(n: int) -> int {\n\treturn 10 / n;\n}\nlet result = first(0);");
+		let mut parser = Parser::new(&mut lexer);
+
+		let error = InterpretorVisitor::new().interpret(parser.ast().unwrap()).unwrap_err();
+
+		assert_eq!(error.to_string(), "Runtime error: Division by zero\n\tat third (line 8)\n\tcalled from second (line 5)\n\tcalled from first (line 2)\n\tcalled from line 10");
+
+		// Every tail call of the recursion is made by the same return, it is kept once
+		let mut lexer = Lexer::new("fn count(n: int) -> int {\n\tif n == 0 {\n\t\treturn 1 / n;\n\t}\n\treturn count(n - 1);\n}\nlet result = count(1000);");
+		let mut parser = Parser::new(&mut lexer);
+
+		let error = InterpretorVisitor::new().interpret(parser.ast().unwrap()).unwrap_err();
+
+		assert_eq!(error.to_string(), "Runtime error: Division by zero\n\tat count (line 3)\n\tcalled from count (line 5)\n\tcalled from line 7");
+	}
+
+	#[test]
+	fn integer_overflow() {
+		let mut lexer = Lexer::new("let max = 9223372036854775807;\nlet result = max + 1;");
+		let mut parser = Parser::new(&mut lexer);
+
+		let error = InterpretorVisitor::new().interpret(parser.ast().unwrap()).unwrap_err();
+
+		assert_eq!(error.message, "Integer overflow");
+		assert_eq!(error.span, Some(Span::new(1, 17)));
+	}
+
+	#[test]
+	fn wrapping_and_saturating_builtins() {
+		let mut interpretor = interpret("
+			let max = 9223372036854775807;
+			let wrapped = wrapping_add(max, 1);
+			let saturated = saturating_mul(max, 2);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("wrapped"), Some(&mut ExpressionResult::Int(i64::MIN)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("saturated"), Some(&mut ExpressionResult::Int(i64::MAX)));
+	}
+
+	#[test]
+	fn integer_operators() {
+		let mut interpretor = interpret("
+			let modulo = 17 % 5;
+			let power = 2 ** 3 ** 2;
+			let bits = (12 & 10) | (1 << 4) ^ ~0;
+			let shifted = -16 >> 2;
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("modulo"), Some(&mut ExpressionResult::Int(2)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("power"), Some(&mut ExpressionResult::Int(512)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("bits"), Some(&mut ExpressionResult::Int(8 | (16 ^ -1))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("shifted"), Some(&mut ExpressionResult::Int(-4)));
+	}
+
+	#[test]
+	fn short_circuit() {
+		let mut interpretor = interpret("
+			let x = 0;
+			let and_result = x != 0 && 10 / x > 1;
+			let or_result = x == 0 || 10 / x > 1;
+			let precedence = true || false && false;
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("and_result"), Some(&mut ExpressionResult::Bool(false)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("or_result"), Some(&mut ExpressionResult::Bool(true)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("precedence"), Some(&mut ExpressionResult::Bool(true)));
+	}
+
+	#[test]
+	fn casts() {
+		let mut interpretor = interpret("
+			let count = 3;
+			let mixed = count as float * 2.5;
+			let truncated = -7.9 as int;
+			let flag = true as int + 1;
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("mixed"), Some(&mut ExpressionResult::Float(7.5)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("truncated"), Some(&mut ExpressionResult::Int(-7)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("flag"), Some(&mut ExpressionResult::Int(2)));
+	}
+
+	#[test]
+	fn out_of_range_cast() {
+		let mut lexer = Lexer::new("let big = (10.0 ** 30.0) as int;");
+		let mut parser = Parser::new(&mut lexer);
+
+		let error = InterpretorVisitor::new().interpret(parser.ast().unwrap()).unwrap_err();
+
+		assert_eq!(error.message, "Can't cast 1000000000000000000000000000000 as int");
+		assert_eq!(error.span, Some(Span::new(0, 25)));
+	}
+
+	#[test]
+	fn arrays() {
+		let mut interpretor = interpret("
+			fn first(values: [int]) -> int {
+				let mut copy = values;
+				copy[0] = 10;
+				return copy[0];
+			}
+			let mut grid = [[1, 2], [3, 4]];
+			grid[1][0] = grid[0][1] * 5;
+			let row = grid[1];
+			let size = len(grid) + len(row);
+			let changed = first(row);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("row"), Some(&mut ExpressionResult::Array(Vec::from([ExpressionResult::Int(10), ExpressionResult::Int(4)]))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("size"), Some(&mut ExpressionResult::Int(4)));
+		// Arrays are passed by value
+		assert_eq!(interpretor.scopes.resolve_var_mut("changed"), Some(&mut ExpressionResult::Int(10)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("grid"), Some(&mut ExpressionResult::Array(Vec::from([
+			ExpressionResult::Array(Vec::from([ExpressionResult::Int(1), ExpressionResult::Int(2)])),
+			ExpressionResult::Array(Vec::from([ExpressionResult::Int(10), ExpressionResult::Int(4)]))
+		]))));
+	}
+
+	#[test]
+	fn for_loop() {
+		let mut interpretor = interpret("
+			fn first_negative(values: [int]) -> int {
+				for value in values {
+					if value < 0 {
+						return value;
+					}
 				}
-			},
-			None => panic!("Function not declared")
-		}
+				return 0;
+			}
+			let mut total = 0;
+			let mut values = [1, 2, 3];
+			for value in values {
+				total += value;
+				values = [value];
+			}
+			let found = first_negative([4, -5, -6]);
+		");
 
-		// The function exited, no more exit_point triggered
-		self.exit_point = false;
+		assert_eq!(interpretor.scopes.resolve_var_mut("total"), Some(&mut ExpressionResult::Int(6)));
+		// The elements are the ones of the array when the loop started
+		assert_eq!(interpretor.scopes.resolve_var_mut("values"), Some(&mut ExpressionResult::Array(Vec::from([ExpressionResult::Int(3)]))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("found"), Some(&mut ExpressionResult::Int(-5)));
+		// The variable doesn't outlive the loop
+		assert_eq!(interpretor.scopes.resolve_var_mut("value"), None);
+	}
+
+	#[test]
+	fn compound_assignation() {
+		let mut interpretor = interpret("
+			let mut total = 10;
+			total += 5;
+			total *= 2;
+			total -= 1;
+			total <<= 1;
+			total **= 2;
+			let mut counts = [1, 2];
+			counts[1] += total;
+			counts[0]--;
+			total++;
+			let mut text = \"a\";
+			text += \"b\";
+			let difference = total--total;
+			let negated = --total;
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("total"), Some(&mut ExpressionResult::Int(3365)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("counts"), Some(&mut ExpressionResult::Array(Vec::from([ExpressionResult::Int(0), ExpressionResult::Int(3366)]))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("text"), Some(&mut ExpressionResult::String(String::from("ab"))));
+		// `--` outside of an assignation is two minus
+		assert_eq!(interpretor.scopes.resolve_var_mut("difference"), Some(&mut ExpressionResult::Int(6730)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("negated"), Some(&mut ExpressionResult::Int(3365)));
+	}
+
+	#[test]
+	fn if_expression() {
+		let mut interpretor = interpret("
+			fn sign(x: int) -> int {
+				return if x > 0 { 1 } else if x < 0 { -1 } else { 0 };
+			}
+			let mut calls = 0;
+			let label = if sign(-5) == -1 {
+				calls += 1;
+				let text = \"negative\";
+				text
+			} else {
+				\"positive\"
+			};
+			let zero = sign(0) + if calls == 1 { 10 } else { 20 };
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("label"), Some(&mut ExpressionResult::String(String::from("negative"))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("calls"), Some(&mut ExpressionResult::Int(1)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("zero"), Some(&mut ExpressionResult::Int(10)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("text"), None);
+	}
+
+	#[test]
+	fn if_else_statement() {
+		let mut interpretor = interpret("
+			fn f(a: int) -> int {
+				if a > 0 {
+					return 1;
+				} else {
+					return 2;
+				}
+			}
+			let mut x = 0;
+			if x == 0 {
+				x = 1;
+			} else {
+				x = 2;
+			}
+			let mut y = 0;
+			if x == 0 {
+				y = 1;
+			} else if x == 1 {
+				let inner = 2;
+				y = inner;
+			} else {
+				y = 3;
+			}
+			let positive = f(5);
+			let negative = f(-5);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("x"), Some(&mut ExpressionResult::Int(1)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("y"), Some(&mut ExpressionResult::Int(2)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("inner"), None);
+		assert_eq!(interpretor.scopes.resolve_var_mut("positive"), Some(&mut ExpressionResult::Int(1)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("negative"), Some(&mut ExpressionResult::Int(2)));
+	}
+
+	#[test]
+	fn constants() {
+		let mut interpretor = interpret("
+			const SIZE: int = 10;
+			const AREA: int = SIZE * SIZE;
+			fn scaled(factor: int) -> int {
+				return AREA * factor;
+			}
+			let result = scaled(3);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("result"), Some(&mut ExpressionResult::Int(300)));
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn index_out_of_bounds() {
+		let mut lexer = Lexer::new("let values = [1, 2, 3];\nvalues[3] = 4;");
+		let mut parser = Parser::new(&mut lexer);
+
+		let error = InterpretorVisitor::new().interpret(parser.ast().unwrap()).unwrap_err();
+
+		assert_eq!(error.message, "Index 3 out of bounds for length 3");
+		assert_eq!(error.span, Some(Span::new(1, 0)));
+
+		let mut lexer = Lexer::new("let values = [1, 2, 3];\nlet value = values[-1];");
+		let mut parser = Parser::new(&mut lexer);
+
+		let error = InterpretorVisitor::new().interpret(parser.ast().unwrap()).unwrap_err();
+
+		assert_eq!(error.message, "Index -1 out of bounds for length 3");
+		assert_eq!(error.span, Some(Span::new(1, 18)));
+	}
+
+	#[test]
+	fn structs() {
+		let mut interpretor = interpret("
+			struct Point { x: int, y: int }
+			struct Segment { start: Point, end: Point }
+			fn length(segment: Segment) -> int {
+				return segment.end.x - segment.start.x;
+			}
+			let mut segment = Segment { end: Point { x: 5, y: 0 }, start: Point { x: 1, y: 0 } };
+			segment.start.x = 2;
+			let result = length(segment);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("result"), Some(&mut ExpressionResult::Int(3)));
+	}
+
+	#[test]
+	fn enums_and_match() {
+		let mut interpretor = interpret("
+			enum List { Nil, Cons(int, List) }
+			fn sum(list: List) -> int {
+				return match list {
+					List::Nil => 0,
+					List::Cons(head, tail) => head + sum(tail)
+				};
+			}
+			let list = List::Cons(1, List::Cons(2, List::Cons(3, List::Nil)));
+			let result = sum(list);
+			let second = match list {
+				List::Cons(_, List::Cons(value, _)) => value,
+				_ => -1
+			};
+			let literal = match result { 0 => false, 6 => true, _ => false };
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("result"), Some(&mut ExpressionResult::Int(6)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("second"), Some(&mut ExpressionResult::Int(2)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("literal"), Some(&mut ExpressionResult::Bool(true)));
+		// Arm bindings don't outlive the arm
+		assert_eq!(interpretor.scopes.resolve_var_mut("value"), None);
+	}
+
+	#[test]
+	fn options() {
+		let mut interpretor = interpret("
+			fn find(values: [int], target: int, i: int) -> option<int> {
+				if i == len(values) {
+					return none;
+				}
+				if values[i] == target {
+					return some(i);
+				}
+				return find(values, target, i + 1);
+			}
+			let values = [4, 8, 15];
+			let found = match find(values, 8, 0) { some(i) => i, none => -1 };
+			let missing = match find(values, 16, 0) { some(i) => i, none => -1 };
+			let mut unwrapped = 0;
+			if let some(i) = find(values, 15, 0) {
+				unwrapped = i;
+			}
+			let nested: option<option<int>> = some(none);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("found"), Some(&mut ExpressionResult::Int(1)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("missing"), Some(&mut ExpressionResult::Int(-1)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("unwrapped"), Some(&mut ExpressionResult::Int(2)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("nested"), Some(&mut ExpressionResult::Some(Box::new(ExpressionResult::None))));
+		// `if let` bindings don't outlive the body
+		assert_eq!(interpretor.scopes.resolve_var_mut("i"), None);
+	}
+
+	#[test]
+	fn results() {
+		let mut interpretor = interpret("
+			fn half(value: int) -> result<int, string> {
+				if value % 2 == 1 {
+					return err(\"odd\");
+				}
+				return ok(value / 2);
+			}
+			fn quarter(value: int) -> result<int, string> {
+				let first = half(value)?;
+				return ok(half(first)? + 1 - 1);
+			}
+			let even = quarter(8);
+			let odd = quarter(6);
+			let parsed = match parse_int(\"12\") { ok(value) => value, err(_) => 0 };
+			let mut message = \"\";
+			if let err(error) = parse_int(\"x\") {
+				message = error;
+			}
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("even"), Some(&mut ExpressionResult::Ok(Box::new(ExpressionResult::Int(2)))));
+		// The error stops `quarter` before the second call
+		assert_eq!(interpretor.scopes.resolve_var_mut("odd"), Some(&mut ExpressionResult::Err(Box::new(ExpressionResult::String(String::from("odd"))))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("parsed"), Some(&mut ExpressionResult::Int(12)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("message"), Some(&mut ExpressionResult::String(String::from("Invalid integer \"x\""))));
+	}
+
+	#[test]
+	fn generic_functions() {
+		let mut interpretor = interpret("
+			fn max<T: Ord>(a: T, b: T) -> T {
+				if a > b {
+					return a;
+				}
+				return b;
+			}
+			fn first<T>(values: [T]) -> option<T> {
+				if len(values) == 0 {
+					return none;
+				}
+				return some(values[0]);
+			}
+			let int_max = max(3, 7);
+			let float_max = max(2.5, 1.5);
+			let string_max = max(\"abc\", \"abd\");
+			let head = first([true, false]);
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("int_max"), Some(&mut ExpressionResult::Int(7)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("float_max"), Some(&mut ExpressionResult::Float(2.5)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("string_max"), Some(&mut ExpressionResult::String(String::from("abd"))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("head"), Some(&mut ExpressionResult::Some(Box::new(ExpressionResult::Bool(true)))));
+	}
+
+	#[test]
+	fn strings() {
+		let mut interpretor = interpret("
+			let greeting = \"hello\" + \" \" + \"bob\";
+			let size = len(greeting);
+			let ordered = \"alice\" < \"bob\" && greeting != \"hello\";
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("greeting"), Some(&mut ExpressionResult::String(String::from("hello bob"))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("size"), Some(&mut ExpressionResult::Int(9)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("ordered"), Some(&mut ExpressionResult::Bool(true)));
+	}
+
+	#[test]
+	fn maps() {
+		let mut interpretor = interpret("
+			let mut ages = map { \"alice\": 30, \"bob\": 25 };
+			ages[\"carol\"] = 41;
+			ages[\"bob\"] = ages[\"bob\"] + 1;
+			ages = remove(ages, \"alice\");
+			let known = contains(ages, \"carol\") && !contains(ages, \"alice\");
+			let mut names = \"\";
+			for name in ages {
+				names = names + name + \" \";
+			}
+		");
+
+		assert_eq!(interpretor.scopes.resolve_var_mut("ages"), Some(&mut ExpressionResult::Map(HashMap::from([
+			(MapKey::String(String::from("bob")), ExpressionResult::Int(26)),
+			(MapKey::String(String::from("carol")), ExpressionResult::Int(41))
+		]))));
+		assert_eq!(interpretor.scopes.resolve_var_mut("known"), Some(&mut ExpressionResult::Bool(true)));
+		assert_eq!(interpretor.scopes.resolve_var_mut("names"), Some(&mut ExpressionResult::String(String::from("bob carol "))));
+	}
+
+	#[test]
+	fn missing_map_key() {
+		let mut lexer = Lexer::new("let ages = map { \"alice\": 30 };\nlet age = ages[\"bob\"];");
+		let mut parser = Parser::new(&mut lexer);
+
+		let error = InterpretorVisitor::new().interpret(parser.ast().unwrap()).unwrap_err();
+
+		assert_eq!(error.message, "Key \"bob\" not found");
+		assert_eq!(error.span, Some(Span::new(1, 14)));
+	}
+}
@@ -2,6 +2,8 @@ use crate::text_iterator::{TextIterator, Symbol};
 use crate::operator::Operator;
 use crate::r#type::Type;
 
+use std::fmt;
+
 #[derive(PartialEq)]
 struct Word {
 	value: String,
@@ -19,13 +21,38 @@ impl Word {
 	}
 
 	fn is_numeric(&self) -> bool {
-		for c in self.value.chars() {
-			if !c.is_numeric() && c != '.' {
-				return false;
-			}
-		}
+		self.value.starts_with(|c: char| Lexer::<'_>::is_number_symbol(c))
+	}
 
-		return true;
+	fn is_comment(&self) -> bool {
+		self.value.starts_with("//") || self.value.starts_with("/*")
+	}
+
+	fn is_prefixed_number(&self) -> bool {
+		["0x", "0X", "0b", "0B", "0o", "0O"].iter().any(|prefix| self.value.starts_with(prefix))
+	}
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LexicalError {
+	MalformedNumber,
+	IntegerOutOfRange,
+	FloatOutOfRange,
+	UnterminatedComment,
+	UnterminatedString,
+	InvalidEscape
+}
+
+impl fmt::Display for LexicalError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			LexicalError::MalformedNumber => write!(f, "Malformed number literal"),
+			LexicalError::IntegerOutOfRange => write!(f, "Integer literal out of range"),
+			LexicalError::FloatOutOfRange => write!(f, "Float literal out of range"),
+			LexicalError::UnterminatedComment => write!(f, "Unterminated block comment"),
+			LexicalError::UnterminatedString => write!(f, "Unterminated string literal"),
+			LexicalError::InvalidEscape => write!(f, "Invalid escape sequence in string literal")
+		}
 	}
 }
 
@@ -36,20 +63,49 @@ pub enum TokenKind {
 	Integer,
 	Float,
 	Bool,
+	String,
 	Identifier,
 	Arrow,
 	LParenthesis,
 	RParenthesis,
 	LBracket,
 	RBracket,
+	LSquareBracket,
+	RSquareBracket,
 	Coma,
+	Dot,
 	Colon,
+	DoubleColon,
+	FatArrow,
 	Assign,
+	// `op=`, the operator is applied to the assigned variable and the value
+	CompoundAssign(Operator),
+	// `++` and `--`, a compound assignation of 1
+	Increment,
+	Decrement,
 	Let,
+	Mut,
+	Const,
 	If,
+	Else,
+	For,
+	In,
 	Fn,
 	Return,
+	As,
+	Struct,
+	Enum,
+	Match,
+	Map,
+	Option,
+	Some,
+	None,
+	Ok,
+	Err,
+	QuestionMark,
 	Semilicon,
+	Comment,
+	Error(LexicalError),
 	Eof
 }
 
@@ -65,7 +121,7 @@ pub struct Token {
 impl Token {
 	fn from_word(kind: TokenKind, word: Word) -> Token {
 		Token {
-			kind: kind,
+			kind,
 			value: word.value,
 			start_col: word.start_col,
 			start_line: word.start_line
@@ -76,19 +132,22 @@ impl Token {
 pub struct Lexer<'a> {
 	program_iterator: TextIterator<'a>,
 	next_symbol: Option<Symbol>,
+	keep_comments: bool
 }
 
 impl Lexer<'_> {
 	// TODO: Change this to static hashmap
-	const RESERVED_KEYWORDS : [&'static str; 32] = 
-	["+", "-", "*", "/", "(", ")", "{", "}", ",", "=", ";", ":", "&&", "||", "==", "!=", ">=", "<=", ">", "<", "!", "->",
-	"let", "true", "false", "if", "fn", "return",
-	"int", "float", "bool", "void"];
+	const RESERVED_KEYWORDS : [&'static str; 75] = 
+	["+", "-", "*", "/", "%", "**", "&", "|", "^", "<<", ">>", "~", "(", ")", "{", "}", "[", "]", ",", ".", "=", ";", ":", "::", "&&", "||", "==", "!=", ">=", "<=", ">", "<", "!", "?", "->", "=>",
+	"+=", "-=", "*=", "/=", "%=", "**=", "&=", "|=", "^=", "<<=", ">>=", "++", "--",
+	"let", "mut", "const", "true", "false", "if", "else", "for", "in", "fn", "return", "as", "struct", "enum", "match", "map", "option", "some", "none", "ok", "err",
+	"int", "float", "bool", "string", "void"];
 
-	pub fn new(program: &str) -> Lexer {
+	pub fn new(program: &str) -> Lexer<'_> {
 		let mut new_lexer = Lexer {
 			program_iterator: TextIterator::new(program),
 			next_symbol: None,
+			keep_comments: false
 		};
 
 		new_lexer.next_symbol = new_lexer.program_iterator.next();
@@ -96,6 +155,14 @@ impl Lexer<'_> {
 		new_lexer
 	}
 
+	// Comments are returned as `TokenKind::Comment` instead of being skipped
+	pub fn with_comments(program: &str) -> Lexer<'_> {
+		let mut new_lexer = Lexer::new(program);
+		new_lexer.keep_comments = true;
+
+		new_lexer
+	}
+
 	fn is_blank_space(value : char) -> bool {
 		value == ' ' || value == '\n' || value == '\t' || value == '\r'
 	}
@@ -105,7 +172,7 @@ impl Lexer<'_> {
 	}
 
 	fn is_number_symbol(value: char) -> bool {
-		value.is_numeric() || value == '.'
+		value.is_ascii_digit()
 	}
 
 	fn next(&mut self) -> Option<Symbol> {
@@ -126,9 +193,132 @@ impl Lexer<'_> {
 		}
 	}
 
+	// `++` and `--` only end an assignation, as in `count++;`, elsewhere they are two operators as in `a--b`
+	fn ends_assignation(&self) -> bool {
+		let mut following = self.program_iterator.clone();
+		while let Some(symbol) = following.next() {
+			if !Lexer::<'_>::is_blank_space(symbol.value) {
+				return symbol.value == ';';
+			}
+		}
+
+		false
+	}
+
+	fn is_comment_start(&self, current_symbol: Symbol) -> bool {
+		current_symbol.value == '/' && matches!(self.next_symbol, Some(symbol) if symbol.value == '/' || symbol.value == '*')
+	}
+
+	// Update the nesting depth of a block comment with its next caractere, return the one to pair with the following
+	fn block_comment_step(previous: Option<char>, current: char, depth: &mut usize) -> Option<char> {
+		match (previous, current) {
+			(Some('/'), '*') => {
+				*depth += 1;
+				None
+			},
+			(Some('*'), '/') => {
+				*depth -= 1;
+				None
+			},
+			_ => Some(current)
+		}
+	}
+
+	fn is_closed_comment(value: &str) -> bool {
+		if value.starts_with("//") {
+			return true;
+		}
+
+		let mut depth = 1;
+		let mut previous = None;
+		for c in value.chars().skip(2) {
+			previous = Lexer::<'_>::block_comment_step(previous, c, &mut depth);
+			if depth == 0 {
+				return true;
+			}
+		}
+
+		false
+	}
+
+	fn comment(&mut self, word: &mut Word) {
+		let Some(symbol) = self.next() else { return };
+		word.value.push(symbol.value);
+
+		if symbol.value == '/' {
+			while let Some(symbol) = self.next_symbol {
+				if symbol.value == '\n' {
+					break;
+				}
+
+				word.value.push(symbol.value);
+				self.next();
+			}
+
+			return;
+		}
+
+		let mut depth = 1;
+		let mut previous = None;
+		while let Some(symbol) = self.next() {
+			word.value.push(symbol.value);
+
+			previous = Lexer::<'_>::block_comment_step(previous, symbol.value, &mut depth);
+			if depth == 0 {
+				break;
+			}
+		}
+	}
+
+	// Take the raw literal up to the closing quote, escapes are decoded by `string_value`
+	fn string(&mut self, word: &mut Word) {
+		while let Some(symbol) = self.next() {
+			word.value.push(symbol.value);
+
+			if symbol.value == '"' {
+				break;
+			}
+
+			if symbol.value == '\\' {
+				if let Some(escaped) = self.next() {
+					word.value.push(escaped.value);
+				}
+			}
+		}
+	}
+
+	fn string_value(raw: &str) -> Result<String, LexicalError> {
+		let mut value = String::new();
+
+		let mut chars = raw.chars().skip(1);
+		while let Some(c) = chars.next() {
+			match c {
+				'"' => return Ok(value),
+				'\\' => match chars.next() {
+					Some('n') => value.push('\n'),
+					Some('t') => value.push('\t'),
+					Some('r') => value.push('\r'),
+					Some('0') => value.push('\0'),
+					Some('\\') => value.push('\\'),
+					Some('"') => value.push('"'),
+					Some(_) => return Err(LexicalError::InvalidEscape),
+					None => break
+				},
+				_ => value.push(c)
+			}
+		}
+
+		Err(LexicalError::UnterminatedString)
+	}
+
+	// Take everything that may belong to the literal, validation is done by `number_kind`
 	fn number(&mut self, word: &mut Word) {
 		while let Some(symbol) = self.next_symbol {
-			if !Lexer::<'_>::is_number_symbol(symbol.value) {
+			let exponent_sign = (symbol.value == '+' || symbol.value == '-')
+				&& !word.is_prefixed_number()
+				&& (word.value.ends_with('e') || word.value.ends_with('E'));
+
+			if !Lexer::<'_>::is_identifier_symbol(symbol.value) && symbol.value != '.' && !exponent_sign {
 				break;
 			}
 
@@ -137,32 +327,146 @@ impl Lexer<'_> {
 		}
 	}
 
+	fn digits(chars: &mut std::iter::Peekable<std::str::Chars>, radix: u32) -> bool {
+		match chars.peek() {
+			Some(c) if c.is_digit(radix) => {},
+			_ => return false
+		}
+
+		while let Some(c) = chars.peek() {
+			if !c.is_digit(radix) && *c != '_' {
+				break;
+			}
+			chars.next();
+		}
+
+		true
+	}
+
+	// Return the kind and the value of the literal, normalized to be parsed by Rust
+	fn number_kind(raw: &str) -> Result<(TokenKind, String), LexicalError> {
+		let radix = match raw.get(0..2) {
+			Some("0x") | Some("0X") => 16,
+			Some("0b") | Some("0B") => 2,
+			Some("0o") | Some("0O") => 8,
+			_ => 10
+		};
+
+		if radix != 10 {
+			let digits = &raw[2..];
+			if !digits.contains(|c: char| c.is_digit(radix)) || !digits.chars().all(|c| c.is_digit(radix) || c == '_') {
+				return Err(LexicalError::MalformedNumber);
+			}
+
+			return match i64::from_str_radix(&digits.replace('_', ""), radix) {
+				Ok(value) => Ok((TokenKind::Integer, value.to_string())),
+				Err(_) => Err(LexicalError::IntegerOutOfRange)
+			};
+		}
+
+		let mut chars = raw.chars().peekable();
+		if !Lexer::<'_>::digits(&mut chars, 10) {
+			return Err(LexicalError::MalformedNumber);
+		}
+
+		let mut is_float = false;
+		if chars.peek() == Some(&'.') {
+			chars.next();
+			is_float = true;
+
+			if !Lexer::<'_>::digits(&mut chars, 10) {
+				return Err(LexicalError::MalformedNumber);
+			}
+		}
+
+		if chars.peek() == Some(&'e') || chars.peek() == Some(&'E') {
+			chars.next();
+			is_float = true;
+
+			if chars.peek() == Some(&'+') || chars.peek() == Some(&'-') {
+				chars.next();
+			}
+
+			if !Lexer::<'_>::digits(&mut chars, 10) {
+				return Err(LexicalError::MalformedNumber);
+			}
+		}
+
+		if chars.next().is_some() {
+			return Err(LexicalError::MalformedNumber);
+		}
+
+		let value = raw.replace('_', "");
+		if is_float {
+			return match value.parse::<f64>() {
+				Ok(float) if float.is_finite() => Ok((TokenKind::Float, value)),
+				_ => Err(LexicalError::FloatOutOfRange)
+			};
+		}
+
+		match value.parse::<i64>() {
+			Ok(_) => Ok((TokenKind::Integer, value)),
+			Err(_) => Err(LexicalError::IntegerOutOfRange)
+		}
+	}
+
 	fn advance(&mut self) -> Option<Word> {
-		let mut current_symbol = self.next();
+		let mut current_symbol = self.next()?;
+
+		loop {
+			while Lexer::<'_>::is_blank_space(current_symbol.value) {
+				current_symbol = self.next()?;
+			}
+
+			if !self.is_comment_start(current_symbol) {
+				break;
+			}
+
+			let mut comment = Word::from_symbol(current_symbol);
+			self.comment(&mut comment);
+
+			if self.keep_comments || !Lexer::<'_>::is_closed_comment(&comment.value) {
+				return Some(comment);
+			}
 
-		while current_symbol != None && Lexer::<'_>::is_blank_space(current_symbol.unwrap().value) {
-			current_symbol = self.next();
+			current_symbol = self.next()?;
 		}
 
-		if current_symbol == None {
-			return None;
+		if current_symbol.value == '"' {
+			let mut word = Word::from_symbol(current_symbol);
+			self.string(&mut word);
+
+			return Some(word);
 		}
-		
-		if let Some(next_symbol) = self.next_symbol { // Handle 2 caractere operator
-			let mut potential_double_op = Word::from_symbol(current_symbol.unwrap());
+
+		// Handle 2 caractere operator, keywords like `if` or `as` are read as whole words
+		if let Some(next_symbol) = self.next_symbol.filter(|_| !Lexer::<'_>::is_identifier_symbol(current_symbol.value)) {
+			let mut potential_double_op = Word::from_symbol(current_symbol);
 			potential_double_op.value.push(next_symbol.value);
 
-			if Lexer::<'_>::RESERVED_KEYWORDS.contains(&potential_double_op.value.as_str()) {
+			let is_step = potential_double_op.value == "++" || potential_double_op.value == "--";
+			if Lexer::<'_>::RESERVED_KEYWORDS.contains(&potential_double_op.value.as_str()) && (!is_step || self.ends_assignation()) {
 				self.next();
+
+				// `**=`, `<<=` and `>>=` are the only 3 caractere operators
+				if let Some(third_symbol) = self.next_symbol {
+					let potential_triple_op = format!("{}{}", potential_double_op.value, third_symbol.value);
+
+					if Lexer::<'_>::RESERVED_KEYWORDS.contains(&potential_triple_op.as_str()) {
+						self.next();
+						potential_double_op.value = potential_triple_op;
+					}
+				}
+
 				return Some(potential_double_op);
 			}
 		}
-		if Lexer::<'_>::RESERVED_KEYWORDS.contains(&current_symbol.unwrap().value.to_string().as_str()) {
-			return Some(Word::from_symbol(current_symbol.unwrap()));
+		if Lexer::<'_>::RESERVED_KEYWORDS.contains(&current_symbol.value.to_string().as_str()) {
+			return Some(Word::from_symbol(current_symbol));
 		}
 
-		let mut word = Word::from_symbol(current_symbol.unwrap());
-		if Lexer::<'_>::is_number_symbol(current_symbol.unwrap().value) {
+		let mut word = Word::from_symbol(current_symbol);
+		if Lexer::<'_>::is_number_symbol(current_symbol.value) {
 			self.number(&mut word);
 		}
 		else {
@@ -178,25 +482,67 @@ impl Lexer<'_> {
 			"-" => TokenKind::Operator(Operator::Minus),
 			"*" => TokenKind::Operator(Operator::Product),
 			"/" => TokenKind::Operator(Operator::Divide),
+			"%" => TokenKind::Operator(Operator::Modulo),
+			"**" => TokenKind::Operator(Operator::Power),
+			"&" => TokenKind::Operator(Operator::BitwiseAnd),
+			"|" => TokenKind::Operator(Operator::BitwiseOr),
+			"^" => TokenKind::Operator(Operator::BitwiseXor),
+			"<<" => TokenKind::Operator(Operator::ShiftLeft),
+			">>" => TokenKind::Operator(Operator::ShiftRight),
+			"~" => TokenKind::Operator(Operator::BitwiseNot),
 			"&&" => TokenKind::Operator(Operator::LogicalAnd),
 			"||" => TokenKind::Operator(Operator::LogicalOr),
 			"(" => TokenKind::LParenthesis,
 			")" => TokenKind::RParenthesis,
 			"{" => TokenKind::LBracket,
 			"}" => TokenKind::RBracket,
+			"[" => TokenKind::LSquareBracket,
+			"]" => TokenKind::RSquareBracket,
 			"," => TokenKind::Coma,
+			"." => TokenKind::Dot,
 			"=" => TokenKind::Assign,
+			"+=" => TokenKind::CompoundAssign(Operator::Add),
+			"-=" => TokenKind::CompoundAssign(Operator::Minus),
+			"*=" => TokenKind::CompoundAssign(Operator::Product),
+			"/=" => TokenKind::CompoundAssign(Operator::Divide),
+			"%=" => TokenKind::CompoundAssign(Operator::Modulo),
+			"**=" => TokenKind::CompoundAssign(Operator::Power),
+			"&=" => TokenKind::CompoundAssign(Operator::BitwiseAnd),
+			"|=" => TokenKind::CompoundAssign(Operator::BitwiseOr),
+			"^=" => TokenKind::CompoundAssign(Operator::BitwiseXor),
+			"<<=" => TokenKind::CompoundAssign(Operator::ShiftLeft),
+			">>=" => TokenKind::CompoundAssign(Operator::ShiftRight),
+			"++" => TokenKind::Increment,
+			"--" => TokenKind::Decrement,
 			";" => TokenKind::Semilicon,
 			":" => TokenKind::Colon,
+			"::" => TokenKind::DoubleColon,
+			"=>" => TokenKind::FatArrow,
 			"let" => TokenKind::Let,
+			"mut" => TokenKind::Mut,
+			"const" => TokenKind::Const,
 			"if" => TokenKind::If,
+			"else" => TokenKind::Else,
+			"for" => TokenKind::For,
+			"in" => TokenKind::In,
 			"fn" => TokenKind::Fn,
 			"return" => TokenKind::Return,
+			"as" => TokenKind::As,
+			"struct" => TokenKind::Struct,
+			"enum" => TokenKind::Enum,
+			"match" => TokenKind::Match,
+			"map" => TokenKind::Map,
+			"option" => TokenKind::Option,
+			"some" => TokenKind::Some,
+			"none" => TokenKind::None,
+			"ok" => TokenKind::Ok,
+			"err" => TokenKind::Err,
 			"true" => TokenKind::Bool,
 			"false" => TokenKind::Bool,
 			"int" => TokenKind::Type(Type::Int),
 			"float" => TokenKind::Type(Type::Float),
 			"bool" => TokenKind::Type(Type::Bool),
+			"string" => TokenKind::Type(Type::String),
 			"void" => TokenKind::Type(Type::Void),
 			"==" => TokenKind::Operator(Operator::Equal),
 			"!=" => TokenKind::Operator(Operator::NotEqual),
@@ -205,6 +551,7 @@ impl Lexer<'_> {
 			">" => TokenKind::Operator(Operator::Greater),
 			"<" => TokenKind::Operator(Operator::Lower),
 			"!" => TokenKind::Operator(Operator::Not),
+			"?" => TokenKind::QuestionMark,
 			"->" => TokenKind::Arrow,
 			_ => panic!("Unknow token")
 		}
@@ -213,7 +560,7 @@ impl Lexer<'_> {
 	pub fn next_token(&mut self) -> Token {
 		let opt_word = self.advance();
 
-		if opt_word == None {
+		if opt_word.is_none() {
 			return Token{ 
 				kind: TokenKind::Eof,
 				value: String::new(),
@@ -224,13 +571,36 @@ impl Lexer<'_> {
 
 		let word : Word = opt_word.unwrap();
 
-		if word.is_numeric() {
-			if word.value.contains('.') {
-				return Token::from_word(TokenKind::Float, word);
-			}
-			else {
-				return Token::from_word(TokenKind::Integer, word);
+		if word.value.starts_with('"') {
+			return match Lexer::<'_>::string_value(&word.value) {
+				Ok(value) => Token {
+					kind: TokenKind::String,
+					value,
+					start_col: word.start_col,
+					start_line: word.start_line
+				},
+				Err(error) => Token::from_word(TokenKind::Error(error), word)
+			};
+		}
+
+		if word.is_comment() {
+			if Lexer::<'_>::is_closed_comment(&word.value) {
+				return Token::from_word(TokenKind::Comment, word);
 			}
+
+			return Token::from_word(TokenKind::Error(LexicalError::UnterminatedComment), word);
+		}
+
+		if word.is_numeric() {
+			return match Lexer::<'_>::number_kind(&word.value) {
+				Ok((kind, value)) => Token {
+					kind,
+					value,
+					start_col: word.start_col,
+					start_line: word.start_line
+				},
+				Err(error) => Token::from_word(TokenKind::Error(error), word)
+			};
 		}
 
 		if Lexer::<'_>::RESERVED_KEYWORDS.contains(&word.value.as_str()) {
@@ -258,6 +628,14 @@ mod tests {
 		expect_token_kind("-", TokenKind::Operator(Operator::Minus));
 		expect_token_kind("*", TokenKind::Operator(Operator::Product));
 		expect_token_kind("/", TokenKind::Operator(Operator::Divide));
+		expect_token_kind("%", TokenKind::Operator(Operator::Modulo));
+		expect_token_kind("**", TokenKind::Operator(Operator::Power));
+		expect_token_kind("&", TokenKind::Operator(Operator::BitwiseAnd));
+		expect_token_kind("|", TokenKind::Operator(Operator::BitwiseOr));
+		expect_token_kind("^", TokenKind::Operator(Operator::BitwiseXor));
+		expect_token_kind("<<", TokenKind::Operator(Operator::ShiftLeft));
+		expect_token_kind(">>", TokenKind::Operator(Operator::ShiftRight));
+		expect_token_kind("~", TokenKind::Operator(Operator::BitwiseNot));
 		expect_token_kind("&&", TokenKind::Operator(Operator::LogicalAnd));
 		expect_token_kind("||", TokenKind::Operator(Operator::LogicalOr));
 		expect_token_kind(">=", TokenKind::Operator(Operator::GreaterOrEq));
@@ -270,6 +648,41 @@ mod tests {
 		expect_token_kind("=", TokenKind::Assign);
 	}
 
+	#[test]
+	fn compound_assign_token() {
+		expect_token_kind("+=", TokenKind::CompoundAssign(Operator::Add));
+		expect_token_kind("-=", TokenKind::CompoundAssign(Operator::Minus));
+		expect_token_kind("*=", TokenKind::CompoundAssign(Operator::Product));
+		expect_token_kind("/=", TokenKind::CompoundAssign(Operator::Divide));
+		expect_token_kind("%=", TokenKind::CompoundAssign(Operator::Modulo));
+		expect_token_kind("**=", TokenKind::CompoundAssign(Operator::Power));
+		expect_token_kind("&=", TokenKind::CompoundAssign(Operator::BitwiseAnd));
+		expect_token_kind("|=", TokenKind::CompoundAssign(Operator::BitwiseOr));
+		expect_token_kind("^=", TokenKind::CompoundAssign(Operator::BitwiseXor));
+		expect_token_kind("<<=", TokenKind::CompoundAssign(Operator::ShiftLeft));
+		expect_token_kind(">>=", TokenKind::CompoundAssign(Operator::ShiftRight));
+
+		expect_token_kind("++;", TokenKind::Increment);
+		expect_token_kind("-- ;", TokenKind::Decrement);
+
+		// Not followed by the end of an assignation, `a--b` is `a - -b`
+		let mut lexer = Lexer::new("a--b --x");
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::Operator(Operator::Minus));
+		assert_eq!(lexer.next_token().kind, TokenKind::Operator(Operator::Minus));
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::Operator(Operator::Minus));
+		assert_eq!(lexer.next_token().kind, TokenKind::Operator(Operator::Minus));
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+
+		let mut lexer = Lexer::new("x<<=1>>2");
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::CompoundAssign(Operator::ShiftLeft));
+		assert_eq!(lexer.next_token().kind, TokenKind::Integer);
+		assert_eq!(lexer.next_token().kind, TokenKind::Operator(Operator::ShiftRight));
+		assert_eq!(lexer.next_token().kind, TokenKind::Integer);
+	}
+
 	#[test]
 	fn integer_token() {
 		expect_token_kind("3325", TokenKind::Integer);
@@ -283,11 +696,114 @@ mod tests {
 		expect_token_kind("3.3333666", TokenKind::Float);
 	}
 
+	#[test]
+	fn number_literal_token() {
+		let mut lexer = Lexer::new("0xff 0b1010 0o17 1_000_000 1e-9 2.5E+3 3e2 0x1e-1");
+
+		let expected = [
+			(TokenKind::Integer, "255"),
+			(TokenKind::Integer, "10"),
+			(TokenKind::Integer, "15"),
+			(TokenKind::Integer, "1000000"),
+			(TokenKind::Float, "1e-9"),
+			(TokenKind::Float, "2.5E+3"),
+			(TokenKind::Float, "3e2"),
+			(TokenKind::Integer, "30"),
+			(TokenKind::Operator(Operator::Minus), "-"),
+			(TokenKind::Integer, "1")
+		];
+		for (kind, value) in expected {
+			let token = lexer.next_token();
+
+			assert_eq!(token.kind, kind);
+			assert_eq!(token.value, value);
+		}
+	}
+
+	#[test]
+	fn malformed_number_token() {
+		expect_token_kind("1.2.3", TokenKind::Error(LexicalError::MalformedNumber));
+		expect_token_kind("12abc", TokenKind::Error(LexicalError::MalformedNumber));
+		expect_token_kind("1.", TokenKind::Error(LexicalError::MalformedNumber));
+		expect_token_kind("1e", TokenKind::Error(LexicalError::MalformedNumber));
+		expect_token_kind("0x", TokenKind::Error(LexicalError::MalformedNumber));
+		expect_token_kind("0b102", TokenKind::Error(LexicalError::MalformedNumber));
+		expect_token_kind("9223372036854775808", TokenKind::Error(LexicalError::IntegerOutOfRange));
+		expect_token_kind("0x1_0000_0000_0000_0000", TokenKind::Error(LexicalError::IntegerOutOfRange));
+		expect_token_kind("1e999", TokenKind::Error(LexicalError::FloatOutOfRange));
+		expect_token_kind("٣", TokenKind::Identifier);
+	}
+
+	#[test]
+	fn string_token() {
+		let mut lexer = Lexer::new("\"key\" \"tab\\t \\\"quoted\\\" // kept\" \"\"");
+
+		let token = lexer.next_token();
+		assert_eq!(token.kind, TokenKind::String);
+		assert_eq!(token.value, "key");
+
+		let token = lexer.next_token();
+		assert_eq!(token.kind, TokenKind::String);
+		assert_eq!(token.value, "tab\t \"quoted\" // kept");
+
+		let token = lexer.next_token();
+		assert_eq!(token.kind, TokenKind::String);
+		assert_eq!(token.value, "");
+
+		expect_token_kind("\"open", TokenKind::Error(LexicalError::UnterminatedString));
+		expect_token_kind("\"open\\\"", TokenKind::Error(LexicalError::UnterminatedString));
+		expect_token_kind("\"\\q\"", TokenKind::Error(LexicalError::InvalidEscape));
+	}
+
+	#[test]
+	fn comment() {
+		let mut lexer = Lexer::new("a // line comment\n/ /* block /* nested */ comment */ b /**/ //");
+
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::Operator(Operator::Divide));
+
+		let token = lexer.next_token();
+		assert_eq!(token.kind, TokenKind::Identifier);
+		assert_eq!(token.value, "b");
+
+		assert_eq!(lexer.next_token().kind, TokenKind::Eof);
+	}
+
+	#[test]
+	fn comment_trivia() {
+		let mut lexer = Lexer::with_comments("// first\na /* second /* nested */ */");
+
+		let token = lexer.next_token();
+		assert_eq!(token.kind, TokenKind::Comment);
+		assert_eq!(token.value, "// first");
+
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+
+		let token = lexer.next_token();
+		assert_eq!(token.kind, TokenKind::Comment);
+		assert_eq!(token.value, "/* second /* nested */ */");
+		assert_eq!(token.start_col, 2);
+		assert_eq!(token.start_line, 1);
+
+		assert_eq!(lexer.next_token().kind, TokenKind::Eof);
+	}
+
+	#[test]
+	fn unterminated_comment() {
+		expect_token_kind("/* /* nested */", TokenKind::Error(LexicalError::UnterminatedComment));
+		expect_token_kind("/*/", TokenKind::Error(LexicalError::UnterminatedComment));
+	}
+
 	#[test]
 	fn identifier_token() {
 		expect_token_kind("identifier", TokenKind::Identifier);
 		expect_token_kind("Testing", TokenKind::Identifier);
 		expect_token_kind("iTesting", TokenKind::Identifier);
+		// Starting like a keyword
+		expect_token_kind("assigned", TokenKind::Identifier);
+		expect_token_kind("iffy", TokenKind::Identifier);
+		expect_token_kind("fnord", TokenKind::Identifier);
+		expect_token_kind("okay", TokenKind::Identifier);
 	}
 
 	#[test]
@@ -296,6 +812,8 @@ mod tests {
 		expect_token_kind(")", TokenKind::RParenthesis);
 		expect_token_kind("{", TokenKind::LBracket);
 		expect_token_kind("}", TokenKind::RBracket);
+		expect_token_kind("[", TokenKind::LSquareBracket);
+		expect_token_kind("]", TokenKind::RSquareBracket);
 	}
 
 	#[test]
@@ -308,8 +826,62 @@ mod tests {
 		expect_token_kind("true", TokenKind::Bool);
 		expect_token_kind("false", TokenKind::Bool);
 		expect_token_kind("let", TokenKind::Let);
+		expect_token_kind("mut", TokenKind::Mut);
+		expect_token_kind("const", TokenKind::Const);
 		expect_token_kind("if", TokenKind::If);
+		expect_token_kind("else", TokenKind::Else);
+		expect_token_kind("for", TokenKind::For);
+		expect_token_kind("in", TokenKind::In);
 		expect_token_kind("return", TokenKind::Return);
+		expect_token_kind("as", TokenKind::As);
+		expect_token_kind("struct", TokenKind::Struct);
+		expect_token_kind("enum", TokenKind::Enum);
+		expect_token_kind("match", TokenKind::Match);
+		expect_token_kind("map", TokenKind::Map);
+		expect_token_kind("option", TokenKind::Option);
+		expect_token_kind("some", TokenKind::Some);
+		expect_token_kind("none", TokenKind::None);
+		expect_token_kind("ok", TokenKind::Ok);
+		expect_token_kind("err", TokenKind::Err);
+		expect_token_kind("string", TokenKind::Type(Type::String));
+	}
+
+	#[test]
+	fn match_arm_token() {
+		let mut lexer = Lexer::new("Shape::Circle(r) => r,");
+
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::DoubleColon);
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::LParenthesis);
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::RParenthesis);
+		assert_eq!(lexer.next_token().kind, TokenKind::FatArrow);
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::Coma);
+	}
+
+	#[test]
+	fn field_access_token() {
+		let mut lexer = Lexer::new("point.x = 1.5;");
+
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::Dot);
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::Assign);
+		assert_eq!(lexer.next_token().kind, TokenKind::Float);
+	}
+
+	#[test]
+	fn try_token() {
+		let mut lexer = Lexer::new("parse(text)?;");
+
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::LParenthesis);
+		assert_eq!(lexer.next_token().kind, TokenKind::Identifier);
+		assert_eq!(lexer.next_token().kind, TokenKind::RParenthesis);
+		assert_eq!(lexer.next_token().kind, TokenKind::QuestionMark);
+		assert_eq!(lexer.next_token().kind, TokenKind::Semilicon);
 	}
 
 	#[test]
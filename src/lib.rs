@@ -2,6 +2,8 @@ pub mod lexer;
 pub mod parser;
 pub mod type_checker;
 pub mod interpretor;
+pub mod code_generator;
+pub mod optimizer;
 
 mod visitor;
 mod operator;
@@ -10,4 +12,9 @@ mod node;
 mod expression_result;
 mod function;
 mod scope;
-mod r#type;
\ No newline at end of file
+mod r#type;
+mod span;
+mod runtime_error;
+mod type_error;
+mod type_warning;
+mod builtin;
\ No newline at end of file
@@ -1,33 +1,75 @@
-//use std::env;
+use std::env;
 use std::fs;
+use std::process;
 
+use jpl::code_generator;
 use jpl::interpretor;
 use jpl::lexer;
+use jpl::optimizer;
 use jpl::parser;
 use jpl::type_checker;
 
 fn main() {
-	// let args: Vec<String> = env::args().collect();
-
-	// if args.len() != 2 {
-	// 	println!("Need one file path as parameter");
-	// 	return;
-	// }
-	// let file_path = &args[1];
-	let contents = fs::read_to_string("exemples/basis.jpl")
+	let args: Vec<String> = env::args().collect();
+
+	let mut file_path = "exemples/basis.jpl";
+	let mut emit_asm = false;
+	let mut optimize = false;
+	let mut dump_ast = false;
+	for arg in args.iter().skip(1) {
+		match arg.as_str() {
+			"--emit-asm" => emit_asm = true,
+			"--optimize" => optimize = true,
+			"--dump-ast" => dump_ast = true,
+			path => file_path = path
+		}
+	}
+
+	let contents = fs::read_to_string(file_path)
 	.expect("Could not read the file {}");
-	
+
 	let mut lexer = lexer::Lexer::new(&contents);
 
 	let mut parser = parser::Parser::new(&mut lexer);
 
-	let ast = parser.ast();
+	let mut ast = parser.ast();
 
-	if let Some(node) = ast {
+	if let Some(node) = &ast {
 		let mut checker = type_checker::TypeCheckerVisitor::new();
-		checker.check(&node);
+		let checked = checker.check(node);
+		for warning in checker.warnings().iter() {
+			eprintln!("{}", warning);
+		}
+		if let Err(errors) = checked {
+			for error in errors.iter() {
+				eprintln!("{}", error);
+			}
+			process::exit(1);
+		}
+	}
+
+	if optimize {
+		if let Some(node) = &ast {
+			ast = optimizer::OptimizerVisitor::new().optimize(node);
+		}
+	}
+
+	if dump_ast {
+		println!("{:#?}", ast);
+		return;
+	}
+
+	if let Some(node) = ast {
+		if emit_asm {
+			let mut generator = code_generator::CodeGeneratorVisitor::new();
+			print!("{}", generator.generate(&node));
+			return;
+		}
 
 		let mut interpretor = interpretor::InterpretorVisitor::new();
-		interpretor.interpret(node);
+		if let Err(error) = interpretor.interpret(node) {
+			eprintln!("{}", error);
+			process::exit(1);
+		}
 	}
-}
\ No newline at end of file
+}
@@ -1,37 +1,181 @@
 use crate::operator::{Operator};
 use crate::r#type::Type;
+use crate::span::Span;
 use crate::visitor::{Visitable, Visitor};
 
+// Step from an assigned variable to the assigned element
+#[derive(Debug, Clone, PartialEq)]
+pub enum Accessor {
+	Index(Node),
+	Field(String)
+}
+
+// `T: Ord + Num` in `fn max<T: Ord + Num>(...)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeParam {
+	pub name: String,
+	pub bounds: Vec<String>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+	Wildcard,
+	Binding(String),
+	Int(i64),
+	Bool(bool),
+	None,
+	Some(Box<Pattern>),
+	Ok(Box<Pattern>),
+	Err(Box<Pattern>),
+	Variant {
+		enum_name: String,
+		variant: String,
+		fields: Vec<Pattern>
+	}
+}
+
+impl Pattern {
+	// Names bound by the pattern, in order
+	pub fn bindings(&self) -> Vec<String> {
+		match self {
+			Pattern::Binding(name) => Vec::from([name.clone()]),
+			Pattern::Some(value) | Pattern::Ok(value) | Pattern::Err(value) => value.bindings(),
+			Pattern::Variant { fields, .. } => fields.iter().flat_map(Pattern::bindings).collect(),
+			_ => Vec::new()
+		}
+	}
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Node {
 	Int(i64),
 	Float(f64),
-	Identifier(String),
+	Identifier {
+		name: String,
+		span: Span
+	},
 	Bool(bool),
+	String(String),
 	BinaryOp {
 		op: Operator,
 		left: Box<Node>,
-		right: Box<Node>
+		right: Box<Node>,
+		span: Span
 	},
 	UnaryOp {
 		op: Operator,
-		right: Box<Node>
+		right: Box<Node>,
+		span: Span
+	},
+	Cast {
+		value: Box<Node>,
+		target: Type,
+		span: Span
+	},
+	Array {
+		elements: Vec<Node>,
+		span: Span
+	},
+	Map {
+		keys: Vec<Node>,
+		values: Vec<Node>,
+		span: Span
+	},
+	None,
+	Some(Box<Node>),
+	Ok(Box<Node>),
+	Err(Box<Node>),
+	Try {
+		value: Box<Node>,
+		span: Span
+	},
+	Index {
+		array: Box<Node>,
+		index: Box<Node>,
+		span: Span
+	},
+	StructLiteral {
+		name: String,
+		field_names: Vec<String>,
+		values: Vec<Node>,
+		span: Span
+	},
+	FieldAccess {
+		value: Box<Node>,
+		field: String,
+		span: Span
+	},
+	EnumVariant {
+		enum_name: String,
+		variant: String,
+		args: Vec<Node>,
+		span: Span
+	},
+	Match {
+		value: Box<Node>,
+		patterns: Vec<Pattern>,
+		arms: Vec<Node>,
+		span: Span
 	},
 	VarDeclaration {
 		name: String,
+		mutable: bool,
 		declared_type: Option<Type>,
-		value: Box<Node>
+		value: Box<Node>,
+		span: Span
+	},
+	// Top level only, `value` is computed at compile time
+	ConstDeclaration {
+		name: String,
+		declared_type: Type,
+		value: Box<Node>,
+		span: Span
 	},
 	VarAssignation {
 		name: String,
-		value: Box<Node>
+		value: Box<Node>,
+		span: Span
+	},
+	ElementAssignation {
+		name: String,
+		path: Vec<Accessor>,
+		value: Box<Node>,
+		span: Span
 	},
 	ReturnStatement {
-		value: Box<Option<Node>>
+		value: Box<Option<Node>>,
+		span: Span
 	},
 	IfStatement {
 		condition: Box<Node>,
-		body: Box<Option<Node>>
+		body: Box<Option<Node>>,
+		// An `else if` is an `IfStatement` alone in the else body
+		else_body: Box<Option<Node>>,
+		span: Span
+	},
+	IfLet {
+		pattern: Pattern,
+		value: Box<Node>,
+		body: Box<Option<Node>>,
+		span: Span
+	},
+	ForLoop {
+		variable: String,
+		iterable: Box<Node>,
+		body: Box<Option<Node>>,
+		span: Span
+	},
+	// Branch of an `if` expression, the instructions are followed by the value
+	Block {
+		body: Box<Option<Node>>,
+		value: Box<Node>
+	},
+	IfExpression {
+		condition: Box<Node>,
+		then_branch: Box<Node>,
+		// A `Block`, or an `IfExpression` for `else if`
+		else_branch: Box<Node>,
+		span: Span
 	},
 	InstructionList {
 		current: Box<Node>,
@@ -39,14 +183,60 @@ pub enum Node {
 	},
 	FunctionDeclaration {
 		name: String,
+		type_params: Vec<TypeParam>,
 		param_names: Vec<String>,
+		param_spans: Vec<Span>,
 		param_types: Vec<Type>,
 		return_type: Type,
-		body: Box<Option<Node>>
+		body: Box<Option<Node>>,
+		span: Span
 	},
 	FunctionCall {
 		name: String,
-		args: Vec<Node>
+		args: Vec<Node>,
+		span: Span
+	},
+	StructDeclaration {
+		name: String,
+		field_names: Vec<String>,
+		field_types: Vec<Type>,
+		span: Span
+	},
+	EnumDeclaration {
+		name: String,
+		variant_names: Vec<String>,
+		variant_types: Vec<Vec<Type>>,
+		span: Span
+	}
+}
+
+impl Node {
+	// Direct sub-nodes, in evaluation order
+	pub fn children(&self) -> Vec<&Node> {
+		match self {
+			Node::BinaryOp { left, right, .. } => Vec::from([left.as_ref(), right.as_ref()]),
+			Node::UnaryOp { right: value, .. } | Node::Cast { value, .. } | Node::Some(value) | Node::Ok(value) | Node::Err(value)
+			| Node::Try { value, .. } | Node::FieldAccess { value, .. } | Node::VarDeclaration { value, .. } | Node::ConstDeclaration { value, .. } | Node::VarAssignation { value, .. } => Vec::from([value.as_ref()]),
+			Node::Array { elements, .. } | Node::StructLiteral { values: elements, .. } | Node::EnumVariant { args: elements, .. } | Node::FunctionCall { args: elements, .. } => elements.iter().collect(),
+			Node::Map { keys, values, .. } => keys.iter().zip(values).flat_map(|(key, value)| [key, value]).collect(),
+			Node::Index { array, index, .. } => Vec::from([array.as_ref(), index.as_ref()]),
+			Node::Match { value, arms, .. } => std::iter::once(value.as_ref()).chain(arms).collect(),
+			Node::ElementAssignation { path, value, .. } => path.iter()
+				.filter_map(|accessor| match accessor {
+					Accessor::Index(index) => Some(index),
+					Accessor::Field(_) => None
+				})
+				.chain(std::iter::once(value.as_ref()))
+				.collect(),
+			Node::ReturnStatement { value, .. } => value.iter().collect(),
+			Node::IfStatement { condition, body, else_body, .. } => std::iter::once(condition.as_ref()).chain(body.iter()).chain(else_body.iter()).collect(),
+			Node::IfLet { value, body, .. } | Node::ForLoop { iterable: value, body, .. } => std::iter::once(value.as_ref()).chain(body.iter()).collect(),
+			Node::Block { body, value } => body.iter().chain(std::iter::once(value.as_ref())).collect(),
+			Node::IfExpression { condition, then_branch, else_branch, .. } => Vec::from([condition.as_ref(), then_branch.as_ref(), else_branch.as_ref()]),
+			Node::InstructionList { current, next } => std::iter::once(current.as_ref()).chain(next.iter()).collect(),
+			Node::FunctionDeclaration { body, .. } => body.iter().collect(),
+			_ => Vec::new()
+		}
 	}
 }
 
@@ -55,17 +245,39 @@ impl Visitable for Node {
         match self {
             Node::Int(x) => visitor.visit_int(*x),
             Node::Float(x) => visitor.visit_float(*x),
-			Node::Identifier(name) => visitor.visit_identifier(name),
+			Node::Identifier { name, span } => visitor.visit_identifier(name, span),
 			Node::Bool(value) => visitor.visit_bool(*value),
-            Node::BinaryOp { op, left, right } => visitor.visit_binary_op(op, left, right),
-			Node::UnaryOp { op, right } => visitor.visit_unary_op(op, right),
-			Node::VarDeclaration { name, declared_type, value } => visitor.visit_var_declaration(name, declared_type, value),
-			Node::VarAssignation { name, value } => visitor.visit_var_assignation(name, value),
-			Node::ReturnStatement { value } => visitor.visit_return_statement(value),
-			Node::IfStatement { condition, body } => visitor.visit_if_statement(condition, body),
+			Node::String(value) => visitor.visit_string(value),
+            Node::BinaryOp { op, left, right, span } => visitor.visit_binary_op(op, left, right, span),
+			Node::UnaryOp { op, right, span } => visitor.visit_unary_op(op, right, span),
+			Node::Cast { value, target, span } => visitor.visit_cast(value, target, span),
+			Node::Array { elements, span } => visitor.visit_array(elements, span),
+			Node::Map { keys, values, span } => visitor.visit_map(keys, values, span),
+			Node::None => visitor.visit_none(),
+			Node::Some(value) => visitor.visit_some(value),
+			Node::Ok(value) => visitor.visit_ok(value),
+			Node::Err(value) => visitor.visit_err(value),
+			Node::Try { value, span } => visitor.visit_try(value, span),
+			Node::Index { array, index, span } => visitor.visit_index(array, index, span),
+			Node::StructLiteral { name, field_names, values, span } => visitor.visit_struct_literal(name, field_names, values, span),
+			Node::FieldAccess { value, field, span } => visitor.visit_field_access(value, field, span),
+			Node::EnumVariant { enum_name, variant, args, span } => visitor.visit_enum_variant(enum_name, variant, args, span),
+			Node::Match { value, patterns, arms, span } => visitor.visit_match(value, patterns, arms, span),
+			Node::VarDeclaration { name, mutable, declared_type, value, span } => visitor.visit_var_declaration(name, *mutable, declared_type, value, span),
+			Node::ConstDeclaration { name, declared_type, value, span } => visitor.visit_const_declaration(name, declared_type, value, span),
+			Node::VarAssignation { name, value, span } => visitor.visit_var_assignation(name, value, span),
+			Node::ElementAssignation { name, path, value, span } => visitor.visit_element_assignation(name, path, value, span),
+			Node::ReturnStatement { value, span } => visitor.visit_return_statement(value, span),
+			Node::IfStatement { condition, body, else_body, span } => visitor.visit_if_statement(condition, body, else_body, span),
+			Node::IfLet { pattern, value, body, span } => visitor.visit_if_let(pattern, value, body, span),
+			Node::ForLoop { variable, iterable, body, span } => visitor.visit_for_loop(variable, iterable, body, span),
+			Node::Block { body, value } => visitor.visit_block(body, value),
+			Node::IfExpression { condition, then_branch, else_branch, span } => visitor.visit_if_expression(condition, then_branch, else_branch, span),
 			Node::InstructionList { current, next } => visitor.visit_instruction_list(current, next),
-			Node::FunctionDeclaration { name, param_names, param_types, return_type, body } => visitor.visit_function_declaration(name, param_names, param_types, return_type, body),
-			Node::FunctionCall { name, args } => visitor.visit_function_call(name, args),
+			Node::FunctionDeclaration { name, type_params, param_names, param_spans, param_types, return_type, body, span } => visitor.visit_function_declaration(name, type_params, param_names, param_spans, param_types, return_type, body, span),
+			Node::FunctionCall { name, args, span } => visitor.visit_function_call(name, args, span),
+			Node::StructDeclaration { name, field_names, field_types, span } => visitor.visit_struct_declaration(name, field_names, field_types, span),
+			Node::EnumDeclaration { name, variant_names, variant_types, span } => visitor.visit_enum_declaration(name, variant_names, variant_types, span),
         }
     }
 }
\ No newline at end of file
@@ -4,6 +4,14 @@ pub enum Operator {
 	Minus,
 	Product,
 	Divide,
+	Modulo,
+	Power,
+	BitwiseAnd,
+	BitwiseOr,
+	BitwiseXor,
+	ShiftLeft,
+	ShiftRight,
+	BitwiseNot,
 	LogicalAnd,
 	LogicalOr,
 	Equal,
@@ -18,19 +26,31 @@ pub enum Operator {
 impl Operator {
 	pub fn precedence(&self) -> i16 {
 		match self {
-			Operator::LogicalAnd => 0,
 			Operator::LogicalOr => 0,
-			Operator::LowerOrEq => 1,
-			Operator::GreaterOrEq => 1,
-			Operator::Lower => 1,
-			Operator::Greater => 1,
-			Operator::Equal => 1,
-			Operator::NotEqual => 1,
-			Operator::Add => 2,
-			Operator::Minus => 2,
-			Operator::Product => 3,
-			Operator::Divide => 3,
-			Operator::Not => 4
+			Operator::LogicalAnd => 1,
+			Operator::LowerOrEq => 2,
+			Operator::GreaterOrEq => 2,
+			Operator::Lower => 2,
+			Operator::Greater => 2,
+			Operator::Equal => 2,
+			Operator::NotEqual => 2,
+			Operator::BitwiseOr => 3,
+			Operator::BitwiseXor => 4,
+			Operator::BitwiseAnd => 5,
+			Operator::ShiftLeft => 6,
+			Operator::ShiftRight => 6,
+			Operator::Add => 7,
+			Operator::Minus => 7,
+			Operator::Product => 8,
+			Operator::Divide => 8,
+			Operator::Modulo => 8,
+			Operator::Power => 9,
+			Operator::Not => 10,
+			Operator::BitwiseNot => 10
 		}
 	}
-}
\ No newline at end of file
+
+	pub fn is_right_associative(&self) -> bool {
+		*self == Operator::Power
+	}
+}
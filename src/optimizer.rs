@@ -0,0 +1,842 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::node::{Node, Accessor, Pattern, TypeParam};
+use crate::operator::Operator;
+use crate::visitor::{Visitor, Visitable};
+use crate::r#type::Type;
+use crate::span::Span;
+
+pub struct OptimizerVisitor {
+	result: Option<Node>,
+	// None marks a variable shadowing a constant with a runtime value
+	scopes: Vec<HashMap<String, Option<Node>>>,
+	assigned: HashSet<String>,
+	// Values of the `const` declarations, visible from every function
+	constants: HashMap<String, Node>
+}
+
+impl Default for OptimizerVisitor {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl OptimizerVisitor {
+	pub fn new() -> OptimizerVisitor {
+		OptimizerVisitor {
+			result: None,
+			scopes: Vec::from([ HashMap::new() ]),
+			assigned: HashSet::new(),
+			constants: HashMap::new()
+		}
+	}
+
+	// Value of a constant expression, None when it can't be computed without running the program
+	pub(crate) fn evaluate(node: &Node, constants: &HashMap<String, Node>) -> Option<Node> {
+		let mut optimizer = OptimizerVisitor::new();
+		optimizer.constants = constants.clone();
+
+		optimizer.transform(node).filter(OptimizerVisitor::is_constant)
+	}
+
+	// Fold constant expressions, propagate never reassigned constants and remove dead `if`
+	pub fn optimize(&mut self, ast: &Node) -> Option<Node> {
+		OptimizerVisitor::collect_assignations(ast, &mut self.assigned);
+
+		self.transform(ast)
+	}
+
+	// A variable assigned anywhere is never propagated, whatever its scope
+	fn collect_assignations(node: &Node, assigned: &mut HashSet<String>) {
+		if let Node::VarAssignation { name, .. } | Node::ElementAssignation { name, .. } = node {
+			assigned.insert(name.clone());
+		}
+
+		// Blocks of `if` expressions can hide assignations in any expression
+		for child in node.children() {
+			OptimizerVisitor::collect_assignations(child, assigned);
+		}
+	}
+
+	fn transform(&mut self, node: &Node) -> Option<Node> {
+		node.accept(self);
+
+		self.result.take()
+	}
+
+	fn transform_expression(&mut self, node: &Node) -> Node {
+		self.transform(node).expect("Expression can't be removed")
+	}
+
+	fn transform_body(&mut self, body: &Option<Node>) -> Option<Node> {
+		body.as_ref().and_then(|instruction_list| self.transform(instruction_list))
+	}
+
+	fn insert_var(&mut self, name: &str, value: Option<Node>) {
+		match self.scopes.last_mut() {
+			Some(scope) => { scope.insert(String::from(name), value); },
+			None => panic!("No scope")
+		}
+	}
+
+	fn resolve_constant(&self, name: &str) -> Option<Node> {
+		for scope in self.scopes.iter().rev() {
+			if let Some(value) = scope.get(name) {
+				return value.clone();
+			}
+		}
+
+		self.constants.get(name).cloned()
+	}
+
+	fn is_constant(node: &Node) -> bool {
+		matches!(node, Node::Int(_) | Node::Float(_) | Node::Bool(_))
+	}
+
+	fn fold_binary_op_int(op: &Operator, lhs: i64, rhs: i64) -> Option<Node> {
+		match op {
+			Operator::Add => lhs.checked_add(rhs).map(Node::Int),
+			Operator::Minus => lhs.checked_sub(rhs).map(Node::Int),
+			Operator::Product => lhs.checked_mul(rhs).map(Node::Int),
+			// Division by zero is left to the runtime
+			Operator::Divide => lhs.checked_div(rhs).map(Node::Int),
+			Operator::Modulo => lhs.checked_rem(rhs).map(Node::Int),
+			Operator::Power => u32::try_from(rhs).ok().and_then(|exponent| lhs.checked_pow(exponent)).map(Node::Int),
+			Operator::BitwiseAnd => Some(Node::Int(lhs & rhs)),
+			Operator::BitwiseOr => Some(Node::Int(lhs | rhs)),
+			Operator::BitwiseXor => Some(Node::Int(lhs ^ rhs)),
+			Operator::ShiftLeft if (0..64).contains(&rhs) => Some(Node::Int(lhs << rhs)),
+			Operator::ShiftRight if (0..64).contains(&rhs) => Some(Node::Int(lhs >> rhs)),
+			Operator::LowerOrEq => Some(Node::Bool(lhs <= rhs)),
+			Operator::GreaterOrEq => Some(Node::Bool(lhs >= rhs)),
+			Operator::Equal => Some(Node::Bool(lhs == rhs)),
+			Operator::NotEqual => Some(Node::Bool(lhs != rhs)),
+			Operator::Lower => Some(Node::Bool(lhs < rhs)),
+			Operator::Greater => Some(Node::Bool(lhs > rhs)),
+			_ => None
+		}
+	}
+
+	fn fold_binary_op_float(op: &Operator, lhs: f64, rhs: f64) -> Option<Node> {
+		match op {
+			Operator::Add => Some(Node::Float(lhs + rhs)),
+			Operator::Minus => Some(Node::Float(lhs - rhs)),
+			Operator::Product => Some(Node::Float(lhs * rhs)),
+			Operator::Divide => Some(Node::Float(lhs / rhs)),
+			Operator::Modulo => Some(Node::Float(lhs % rhs)),
+			Operator::Power => Some(Node::Float(lhs.powf(rhs))),
+			Operator::LowerOrEq => Some(Node::Bool(lhs <= rhs)),
+			Operator::GreaterOrEq => Some(Node::Bool(lhs >= rhs)),
+			Operator::Equal => Some(Node::Bool(lhs == rhs)),
+			Operator::NotEqual => Some(Node::Bool(lhs != rhs)),
+			Operator::Lower => Some(Node::Bool(lhs < rhs)),
+			Operator::Greater => Some(Node::Bool(lhs > rhs)),
+			_ => None
+		}
+	}
+
+	fn fold_binary_op_bool(op: &Operator, lhs: bool, rhs: bool) -> Option<Node> {
+		match op {
+			Operator::LogicalAnd => Some(Node::Bool(lhs && rhs)),
+			Operator::LogicalOr => Some(Node::Bool(lhs || rhs)),
+			Operator::Equal => Some(Node::Bool(lhs == rhs)),
+			Operator::NotEqual => Some(Node::Bool(lhs != rhs)),
+			_ => None
+		}
+	}
+
+	fn fold_binary_op(op: &Operator, left: &Node, right: &Node) -> Option<Node> {
+		match (op, left) {
+			// Short-circuit, the right operand is never evaluated
+			(Operator::LogicalAnd, Node::Bool(false)) => return Some(Node::Bool(false)),
+			(Operator::LogicalOr, Node::Bool(true)) => return Some(Node::Bool(true)),
+			(Operator::LogicalAnd, Node::Bool(true)) | (Operator::LogicalOr, Node::Bool(false)) => return Some(right.clone()),
+			_ => {}
+		}
+
+		match (left, right) {
+			(Node::Int(lhs), Node::Int(rhs)) => OptimizerVisitor::fold_binary_op_int(op, *lhs, *rhs),
+			(Node::Float(lhs), Node::Float(rhs)) => OptimizerVisitor::fold_binary_op_float(op, *lhs, *rhs),
+			(Node::Bool(lhs), Node::Bool(rhs)) => OptimizerVisitor::fold_binary_op_bool(op, *lhs, *rhs),
+			_ => None
+		}
+	}
+
+	fn fold_unary_op(op: &Operator, right: &Node) -> Option<Node> {
+		match (op, right) {
+			(Operator::Not, Node::Bool(rhs)) => Some(Node::Bool(!rhs)),
+			(Operator::Minus, Node::Int(rhs)) => rhs.checked_neg().map(Node::Int),
+			(Operator::BitwiseNot, Node::Int(rhs)) => Some(Node::Int(!rhs)),
+			(Operator::Minus, Node::Float(rhs)) => Some(Node::Float(-rhs)),
+			_ => None
+		}
+	}
+}
+
+impl Visitor for OptimizerVisitor {
+	fn visit_int(&mut self, value: i64) {
+		self.result = Some(Node::Int(value));
+	}
+
+	fn visit_float(&mut self, value: f64) {
+		self.result = Some(Node::Float(value));
+	}
+
+	fn visit_identifier(&mut self, name: &str, span: &Span) {
+		self.result = match self.resolve_constant(name) {
+			Some(constant) => Some(constant),
+			None => Some(Node::Identifier { name: String::from(name), span: *span })
+		};
+	}
+
+	fn visit_bool(&mut self, value: bool) {
+		self.result = Some(Node::Bool(value));
+	}
+
+	fn visit_string(&mut self, value: &str) {
+		self.result = Some(Node::String(String::from(value)));
+	}
+
+	fn visit_binary_op(&mut self, op: &Operator, left: &Node, right: &Node, span: &Span) {
+		let left = self.transform_expression(left);
+		let right = self.transform_expression(right);
+
+		self.result = match OptimizerVisitor::fold_binary_op(op, &left, &right) {
+			Some(constant) => Some(constant),
+			None => Some(Node::BinaryOp {
+				op: *op,
+				left: Box::new(left),
+				right: Box::new(right),
+				span: *span
+			})
+		};
+	}
+
+	fn visit_unary_op(&mut self, op: &Operator, right: &Node, span: &Span) {
+		let right = self.transform_expression(right);
+
+		self.result = match OptimizerVisitor::fold_unary_op(op, &right) {
+			Some(constant) => Some(constant),
+			None => Some(Node::UnaryOp {
+				op: *op,
+				right: Box::new(right),
+				span: *span
+			})
+		};
+	}
+
+	fn visit_cast(&mut self, value: &Node, target: &Type, span: &Span) {
+		let value = self.transform_expression(value);
+
+		let folded = match (&value, target) {
+			(Node::Int(value), Type::Float) => Some(Node::Float(*value as f64)),
+			// Out of range values are left to the runtime error
+			(Node::Float(value), Type::Int) if *value >= i64::MIN as f64 && *value < i64::MAX as f64 => Some(Node::Int(*value as i64)),
+			(Node::Bool(value), Type::Int) => Some(Node::Int(*value as i64)),
+			_ => None
+		};
+
+		self.result = match folded {
+			Some(constant) => Some(constant),
+			None => Some(Node::Cast {
+				value: Box::new(value),
+				target: target.clone(),
+				span: *span
+			})
+		};
+	}
+
+	fn visit_array(&mut self, elements: &[Node], span: &Span) {
+		let elements = elements.iter().map(|element| self.transform_expression(element)).collect();
+
+		self.result = Some(Node::Array { elements, span: *span });
+	}
+
+	fn visit_map(&mut self, keys: &[Node], values: &[Node], span: &Span) {
+		let keys = keys.iter().map(|key| self.transform_expression(key)).collect();
+		let values = values.iter().map(|value| self.transform_expression(value)).collect();
+
+		self.result = Some(Node::Map { keys, values, span: *span });
+	}
+
+	fn visit_none(&mut self) {
+		self.result = Some(Node::None);
+	}
+
+	fn visit_some(&mut self, value: &Node) {
+		let value = self.transform_expression(value);
+
+		self.result = Some(Node::Some(Box::new(value)));
+	}
+
+	fn visit_ok(&mut self, value: &Node) {
+		let value = self.transform_expression(value);
+
+		self.result = Some(Node::Ok(Box::new(value)));
+	}
+
+	fn visit_err(&mut self, value: &Node) {
+		let value = self.transform_expression(value);
+
+		self.result = Some(Node::Err(Box::new(value)));
+	}
+
+	fn visit_try(&mut self, value: &Node, span: &Span) {
+		let value = self.transform_expression(value);
+
+		self.result = Some(Node::Try {
+			value: Box::new(value),
+			span: *span
+		});
+	}
+
+	fn visit_index(&mut self, array: &Node, index: &Node, span: &Span) {
+		let array = self.transform_expression(array);
+		let index = self.transform_expression(index);
+
+		// Only constant elements can be dropped, out of bounds indexes are left to the runtime error
+		let folded = match (&array, &index) {
+			(Node::Array { elements, .. }, Node::Int(index)) if elements.iter().all(OptimizerVisitor::is_constant) => {
+				usize::try_from(*index).ok().and_then(|position| elements.get(position).cloned())
+			},
+			_ => None
+		};
+
+		self.result = match folded {
+			Some(constant) => Some(constant),
+			None => Some(Node::Index {
+				array: Box::new(array),
+				index: Box::new(index),
+				span: *span
+			})
+		};
+	}
+
+	fn visit_var_assignation(&mut self, name: &str, value: &Node, span: &Span) {
+		let value = self.transform_expression(value);
+
+		self.result = Some(Node::VarAssignation {
+			name: String::from(name),
+			value: Box::new(value),
+			span: *span
+		});
+	}
+
+	fn visit_element_assignation(&mut self, name: &str, path: &[Accessor], value: &Node, span: &Span) {
+		let path = path.iter().map(|accessor| match accessor {
+			Accessor::Index(index) => Accessor::Index(self.transform_expression(index)),
+			Accessor::Field(field) => Accessor::Field(field.clone())
+		}).collect();
+		let value = self.transform_expression(value);
+
+		self.result = Some(Node::ElementAssignation {
+			name: String::from(name),
+			path,
+			value: Box::new(value),
+			span: *span
+		});
+	}
+
+	fn visit_struct_literal(&mut self, name: &str, field_names: &[String], values: &[Node], span: &Span) {
+		let values = values.iter().map(|value| self.transform_expression(value)).collect();
+
+		self.result = Some(Node::StructLiteral {
+			name: String::from(name),
+			field_names: field_names.to_vec(),
+			values,
+			span: *span
+		});
+	}
+
+	fn visit_enum_variant(&mut self, enum_name: &str, variant: &str, args: &[Node], span: &Span) {
+		let args = args.iter().map(|arg| self.transform_expression(arg)).collect();
+
+		self.result = Some(Node::EnumVariant {
+			enum_name: String::from(enum_name),
+			variant: String::from(variant),
+			args,
+			span: *span
+		});
+	}
+
+	fn visit_match(&mut self, value: &Node, patterns: &[Pattern], arms: &[Node], span: &Span) {
+		let value = self.transform_expression(value);
+
+		let mut transformed_arms = Vec::new();
+		for (pattern, arm) in patterns.iter().zip(arms) {
+			// Bindings hide the constants with the same name
+			self.scopes.push(HashMap::new());
+			for binding in pattern.bindings() {
+				self.insert_var(&binding, None);
+			}
+
+			transformed_arms.push(self.transform_expression(arm));
+			self.scopes.pop();
+		}
+
+		self.result = Some(Node::Match {
+			value: Box::new(value),
+			patterns: patterns.to_vec(),
+			arms: transformed_arms,
+			span: *span
+		});
+	}
+
+	fn visit_field_access(&mut self, value: &Node, field: &str, span: &Span) {
+		let value = self.transform_expression(value);
+
+		self.result = Some(Node::FieldAccess {
+			value: Box::new(value),
+			field: String::from(field),
+			span: *span
+		});
+	}
+
+	fn visit_const_declaration(&mut self, name: &str, declared_type: &Type, value: &Node, span: &Span) {
+		let value = self.transform_expression(value);
+
+		if OptimizerVisitor::is_constant(&value) {
+			self.constants.insert(String::from(name), value.clone());
+		}
+
+		self.result = Some(Node::ConstDeclaration {
+			name: String::from(name),
+			declared_type: declared_type.clone(),
+			value: Box::new(value),
+			span: *span
+		});
+	}
+
+	fn visit_var_declaration(&mut self, name: &str, mutable: bool, declared_type: &Option<Type>, value: &Node, span: &Span) {
+		let value = self.transform_expression(value);
+
+		if OptimizerVisitor::is_constant(&value) && !self.assigned.contains(name) {
+			self.insert_var(name, Some(value.clone()));
+		}
+		else {
+			self.insert_var(name, None);
+		}
+
+		self.result = Some(Node::VarDeclaration {
+			name: String::from(name),
+			mutable,
+			declared_type: declared_type.clone(),
+			value: Box::new(value),
+			span: *span
+		});
+	}
+
+	fn visit_return_statement(&mut self, value: &Option<Node>, span: &Span) {
+		let value = value.as_ref().map(|exp| self.transform_expression(exp));
+
+		self.result = Some(Node::ReturnStatement { value: Box::new(value), span: *span });
+	}
+
+	fn visit_if_statement(&mut self, condition: &Node, body: &Option<Node>, else_body: &Option<Node>, span: &Span) {
+		let condition = self.transform_expression(condition);
+
+		// Only the body taken is kept, still in an `if` to drop its variables at its end
+		let (condition, body, else_body) = match condition {
+			Node::Bool(true) => (condition, body, &None),
+			Node::Bool(false) => (Node::Bool(true), else_body, &None),
+			_ => (condition, body, else_body)
+		};
+
+		self.scopes.push(HashMap::new());
+		let body = self.transform_body(body);
+		self.scopes.pop();
+
+		self.scopes.push(HashMap::new());
+		let else_body = self.transform_body(else_body);
+		self.scopes.pop();
+
+		if body.is_none() && else_body.is_none() {
+			// Dead code
+			self.result = None;
+			return;
+		}
+
+		self.result = Some(Node::IfStatement {
+			condition: Box::new(condition),
+			body: Box::new(body),
+			else_body: Box::new(else_body),
+			span: *span
+		});
+	}
+
+	fn visit_if_let(&mut self, pattern: &Pattern, value: &Node, body: &Option<Node>, span: &Span) {
+		let value = self.transform_expression(value);
+
+		// Bindings hide the constants with the same name
+		self.scopes.push(HashMap::new());
+		for binding in pattern.bindings() {
+			self.insert_var(&binding, None);
+		}
+		let body = self.transform_body(body);
+		self.scopes.pop();
+
+		self.result = Some(Node::IfLet {
+			pattern: pattern.clone(),
+			value: Box::new(value),
+			body: Box::new(body),
+			span: *span
+		});
+	}
+
+	fn visit_for_loop(&mut self, variable: &str, iterable: &Node, body: &Option<Node>, span: &Span) {
+		let iterable = self.transform_expression(iterable);
+
+		// The variable hides the constant with the same name
+		self.scopes.push(HashMap::new());
+		self.insert_var(variable, None);
+		let body = self.transform_body(body);
+		self.scopes.pop();
+
+		self.result = Some(Node::ForLoop {
+			variable: String::from(variable),
+			iterable: Box::new(iterable),
+			body: Box::new(body),
+			span: *span
+		});
+	}
+
+	fn visit_block(&mut self, body: &Option<Node>, value: &Node) {
+		self.scopes.push(HashMap::new());
+		let body = self.transform_body(body);
+		let value = self.transform_expression(value);
+		self.scopes.pop();
+
+		self.result = match body {
+			Some(_) => Some(Node::Block {
+				body: Box::new(body),
+				value: Box::new(value)
+			}),
+			// Its scope is useless without instructions
+			None => Some(value)
+		};
+	}
+
+	fn visit_if_expression(&mut self, condition: &Node, then_branch: &Node, else_branch: &Node, span: &Span) {
+		let condition = self.transform_expression(condition);
+
+		// Only the branch taken is kept
+		self.result = match condition {
+			Node::Bool(true) => self.transform(then_branch),
+			Node::Bool(false) => self.transform(else_branch),
+			_ => Some(Node::IfExpression {
+				condition: Box::new(condition),
+				then_branch: Box::new(self.transform_expression(then_branch)),
+				else_branch: Box::new(self.transform_expression(else_branch)),
+				span: *span
+			})
+		};
+	}
+
+	fn visit_instruction_list(&mut self, current: &Node, next: &Option<Node>) {
+		let current = self.transform(current);
+		let next = self.transform_body(next);
+
+		self.result = match current {
+			Some(current_node) => Some(Node::InstructionList {
+				current: Box::new(current_node),
+				next: Box::new(next)
+			}),
+			None => next
+		};
+	}
+
+	fn visit_function_declaration(&mut self, name: &str, type_params: &[TypeParam], param_names: &[String], param_spans: &[Span], param_types: &[Type], return_type: &Type, body: &Option<Node>, span: &Span) {
+		// Functions don't see the caller variables
+		let caller_scopes = std::mem::replace(&mut self.scopes, Vec::from([ HashMap::new() ]));
+		for param in param_names.iter() {
+			self.insert_var(param, None);
+		}
+
+		let body = self.transform_body(body);
+
+		self.scopes = caller_scopes;
+
+		self.result = Some(Node::FunctionDeclaration {
+			name: String::from(name),
+			type_params: type_params.to_vec(),
+			param_names: param_names.to_vec(),
+			param_spans: param_spans.to_vec(),
+			param_types: param_types.to_vec(),
+			return_type: return_type.clone(),
+			body: Box::new(body),
+			span: *span
+		});
+	}
+
+	fn visit_function_call(&mut self, name: &str, args: &[Node], span: &Span) {
+		let args = args.iter().map(|arg| self.transform_expression(arg)).collect();
+
+		self.result = Some(Node::FunctionCall {
+			name: String::from(name),
+			args,
+			span: *span
+		});
+	}
+	fn visit_struct_declaration(&mut self, name: &str, field_names: &[String], field_types: &[Type], span: &Span) {
+		self.result = Some(Node::StructDeclaration {
+			name: String::from(name),
+			field_names: field_names.to_vec(),
+			field_types: field_types.to_vec(),
+			span: *span
+		});
+	}
+
+	fn visit_enum_declaration(&mut self, name: &str, variant_names: &[String], variant_types: &[Vec<Type>], span: &Span) {
+		self.result = Some(Node::EnumDeclaration {
+			name: String::from(name),
+			variant_names: variant_names.to_vec(),
+			variant_types: variant_types.to_vec(),
+			span: *span
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::lexer::Lexer;
+	use crate::parser::Parser;
+
+	fn optimize(program: &str) -> Option<Node> {
+		let mut lexer = Lexer::new(program);
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast().unwrap();
+
+		OptimizerVisitor::new().optimize(&ast)
+	}
+
+	#[test]
+	fn constant_folding() {
+		assert_eq!(optimize("let test = (-6 * 2) + (2 * 3) + 3;"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration {
+					name: String::from("test"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::Int(-3)),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+
+		assert_eq!(optimize("let test = !(2.5 > 3.5) && true;"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration {
+					name: String::from("test"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::Bool(true)),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn short_circuit_folding() {
+		assert_eq!(optimize("let test = false && foo();"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration {
+					name: String::from("test"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::Bool(false)),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn division_by_zero_not_folded() {
+		assert_eq!(optimize("let test = 1 / 0;"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration {
+					name: String::from("test"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::BinaryOp {
+						op: Operator::Divide,
+						left: Box::new(Node::Int(1)),
+						right: Box::new(Node::Int(0)),
+						span: Span::new(0, 13)
+					}),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn dead_if_elimination() {
+		assert_eq!(optimize("if 1 > 2 { let test = 3; }"), None);
+	}
+
+	#[test]
+	fn constant_propagation() {
+		assert_eq!(optimize("let a = 2; let b = a * 3; let c = 1; c = b;"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration {
+					name: String::from("a"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::Int(2)),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::VarDeclaration {
+						name: String::from("b"),
+						mutable: false,
+						declared_type: None,
+						value: Box::new(Node::Int(6)),
+						span: Span::new(0, 11)
+					}),
+					next: Box::new(Some(Node::InstructionList {
+						current: Box::new(Node::VarDeclaration {
+							name: String::from("c"),
+							mutable: false,
+							declared_type: None,
+							value: Box::new(Node::Int(1)),
+							span: Span::new(0, 26)
+						}),
+						next: Box::new(Some(Node::InstructionList {
+							current: Box::new(Node::VarAssignation {
+								name: String::from("c"),
+								value: Box::new(Node::Int(6)),
+								span: Span::new(0, 37)
+							}),
+							next: Box::new(None)
+						}))
+					}))
+				}))
+			}
+		));
+	}
+
+	#[test]
+	fn shadowed_constant_not_propagated() {
+		assert_eq!(optimize("let a = 2; if b { let a = b; return a; }"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration {
+					name: String::from("a"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::Int(2)),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::IfStatement {
+						condition: Box::new(Node::Identifier { name: String::from("b"), span: Span::new(0, 14) }),
+						body: Box::new(Some(Node::InstructionList {
+							current: Box::new(Node::VarDeclaration {
+								name: String::from("a"),
+								mutable: false,
+								declared_type: None,
+								value: Box::new(Node::Identifier { name: String::from("b"), span: Span::new(0, 26) }),
+								span: Span::new(0, 18)
+							}),
+							next: Box::new(Some(Node::InstructionList {
+								current: Box::new(Node::ReturnStatement {
+									value: Box::new(Some(Node::Identifier { name: String::from("a"), span: Span::new(0, 36) })),
+									span: Span::new(0, 29)
+								}),
+								next: Box::new(None)
+							}))
+						})),
+						else_body: Box::new(None),
+						span: Span::new(0, 11)
+					}),
+					next: Box::new(None)
+				}))
+			}
+		));
+	}
+
+	#[test]
+	fn constant_index_folding() {
+		assert_eq!(optimize("let test = [1, 2 * 3][1]; let kept = [1, 2][2];"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration {
+					name: String::from("test"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::Int(6)),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::VarDeclaration {
+						name: String::from("kept"),
+						mutable: false,
+						declared_type: None,
+						value: Box::new(Node::Index {
+							array: Box::new(Node::Array { elements: Vec::from([Node::Int(1), Node::Int(2)]), span: Span::new(0, 37) }),
+							index: Box::new(Node::Int(2)),
+							span: Span::new(0, 43)
+						}),
+						span: Span::new(0, 26)
+					}),
+					next: Box::new(None)
+				}))
+			}
+		));
+	}
+
+	#[test]
+	fn constant_if_expression_folding() {
+		assert_eq!(optimize("let test = if 1 < 2 { 3 } else { 4 };"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration {
+					name: String::from("test"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::Int(3)),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn constant_declaration_propagation() {
+		assert_eq!(optimize("const MAX: int = 10 * 10; fn limit() { return MAX; }"), Some(
+			Node::InstructionList {
+				current: Box::new(Node::ConstDeclaration {
+					name: String::from("MAX"),
+					declared_type: Type::Int,
+					value: Box::new(Node::Int(100)),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::FunctionDeclaration {
+						name: String::from("limit"),
+						type_params: Vec::new(),
+						param_names: Vec::new(),
+						param_spans: Vec::new(),
+						param_types: Vec::new(),
+						return_type: Type::Infer,
+						body: Box::new(Some(Node::InstructionList {
+							current: Box::new(Node::ReturnStatement {
+								value: Box::new(Some(Node::Int(100))),
+								span: Span::new(0, 39)
+							}),
+							next: Box::new(None)
+						})),
+						span: Span::new(0, 29)
+					}),
+					next: Box::new(None)
+				}))
+			}
+		));
+	}
+}
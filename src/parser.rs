@@ -2,8 +2,9 @@ use core::panic;
 
 use crate::operator::{Operator};
 use crate::lexer::{Lexer, Token, TokenKind};
-use crate::node::{Node};
+use crate::node::{Node, Accessor, Pattern, TypeParam};
 use crate::r#type::Type;
+use crate::span::Span;
 
 fn to_bool(value: &str) -> bool {
 	value == "true"
@@ -11,21 +12,39 @@ fn to_bool(value: &str) -> bool {
 
 pub struct Parser<'a> {
 	lexer: &'a mut Lexer<'a>,
-	current_token: Token
+	current_token: Token,
+	// False in `if` conditions, where `{` opens the body
+	struct_literal_allowed: bool
 }
 
 impl Parser<'_> {
 	pub fn new<'a>(lexer: &'a mut Lexer<'a>) -> Parser<'a> {
-		let next_token = lexer.next_token();
+		let next_token = Parser::next_token(lexer);
 
 		Parser {
-			lexer: lexer,
-			current_token: next_token
+			lexer,
+			current_token: next_token,
+			struct_literal_allowed: true
 		}
 	}
 
+	// Comments kept by the lexer are trivia for the parser
+	fn next_token(lexer: &mut Lexer) -> Token {
+		let mut token = lexer.next_token();
+		while token.kind == TokenKind::Comment {
+			token = lexer.next_token();
+		}
+
+		if let TokenKind::Error(error) = token.kind {
+			// TODO: Better error handling
+			panic!("{} '{}' at {}", error, token.value, Span::new(token.start_line, token.start_col));
+		}
+
+		token
+	}
+
 	fn advance(&mut self) {
-		self.current_token = self.lexer.next_token();
+		self.current_token = Parser::next_token(self.lexer);
 	}
 
 	fn eat(&mut self, kind: TokenKind) {
@@ -40,9 +59,164 @@ impl Parser<'_> {
 		self.current_token.kind == kind
 	}
 
-	fn parse_args(&mut self) -> Vec<Node> {
+	fn span(&self) -> Span {
+		Span::new(self.current_token.start_line, self.current_token.start_col)
+	}
+
+	fn parse_type(&mut self) -> Type {
+		match &self.current_token.kind {
+			TokenKind::Type(parsed_type) => {
+				let parsed_type = parsed_type.clone();
+				self.advance();
+
+				parsed_type
+			},
+			TokenKind::LSquareBracket => {
+				self.advance();
+
+				let element_type = self.parse_type();
+
+				self.eat(TokenKind::RSquareBracket);
+
+				Type::Array(Box::new(element_type))
+			},
+			TokenKind::Option => {
+				self.advance();
+
+				self.eat(TokenKind::Operator(Operator::Lower));
+
+				let value_type = self.parse_type();
+
+				self.eat_closing_angle();
+
+				Type::Option(Box::new(value_type))
+			},
+			// Not a keyword, `result` is a common variable name
+			TokenKind::Identifier if self.current_token.value == "result" => {
+				self.advance();
+
+				self.eat(TokenKind::Operator(Operator::Lower));
+
+				let value_type = self.parse_type();
+
+				self.eat(TokenKind::Coma);
+
+				let error_type = self.parse_type();
+
+				self.eat_closing_angle();
+
+				Type::Result(Box::new(value_type), Box::new(error_type))
+			},
+			TokenKind::Map => {
+				self.advance();
+
+				self.eat(TokenKind::LSquareBracket);
+
+				let key_type = self.parse_type();
+
+				self.eat(TokenKind::Coma);
+
+				let value_type = self.parse_type();
+
+				self.eat(TokenKind::RSquareBracket);
+
+				Type::Map(Box::new(key_type), Box::new(value_type))
+			},
+			TokenKind::Identifier => {
+				let name = self.current_token.value.clone();
+				self.advance();
+
+				Type::Named(name)
+			},
+			_ => panic!("Not a valid type") // TODO: Better error handling
+		}
+	}
+
+	// `>>` closes two nested types, as in `option<option<int>>`
+	fn eat_closing_angle(&mut self) {
+		if self.expect(TokenKind::Operator(Operator::ShiftRight)) {
+			self.current_token.kind = TokenKind::Operator(Operator::Greater);
+			self.current_token.value = String::from(">");
+			self.current_token.start_col += 1;
+			return;
+		}
+
+		self.eat(TokenKind::Operator(Operator::Greater));
+	}
+
+	fn parse_type_params(&mut self) -> Vec<TypeParam> {
+		let mut type_params = Vec::new();
+		if !self.expect(TokenKind::Operator(Operator::Lower)) {
+			return type_params;
+		}
+		self.advance();
+
+		loop {
+			let name = self.current_token.value.clone();
+			self.eat(TokenKind::Identifier);
+
+			let mut bounds = Vec::new();
+			if self.expect(TokenKind::Colon) {
+				self.advance();
+
+				bounds.push(self.current_token.value.clone());
+				self.eat(TokenKind::Identifier);
+
+				while self.expect(TokenKind::Operator(Operator::Add)) {
+					self.advance();
+
+					bounds.push(self.current_token.value.clone());
+					self.eat(TokenKind::Identifier);
+				}
+			}
+
+			type_params.push(TypeParam { name, bounds });
+
+			if !self.expect(TokenKind::Coma) {
+				break;
+			}
+			self.advance();
+		}
+
+		self.eat(TokenKind::Operator(Operator::Greater));
+
+		type_params
+	}
+
+	// Inferred by the type checker when omitted
+	fn parse_param_type(&mut self) -> Type {
+		if !self.expect(TokenKind::Colon) {
+			return Type::Infer;
+		}
+		self.advance();
+
+		self.parse_type()
+	}
+
+	// Value of `some(...)`, `ok(...)` and `err(...)`
+	fn parenthesized_expression(&mut self) -> Node {
+		self.eat(TokenKind::LParenthesis);
+
+		let value = self.parse_expression();
+
+		self.eat(TokenKind::RParenthesis);
+
+		value
+	}
+
+	fn parenthesized_pattern(&mut self) -> Pattern {
+		self.eat(TokenKind::LParenthesis);
+
+		let value = self.parse_pattern();
+
+		self.eat(TokenKind::RParenthesis);
+
+		value
+	}
+
+	fn parse_args(&mut self, end: TokenKind) -> Vec<Node> {
 		let mut args : Vec<Node> = Vec::new();
-		if self.current_token.kind != TokenKind::RParenthesis {
+		if self.current_token.kind != end {
 			args.push(self.parse_expression());
 
 			while self.current_token.kind == TokenKind::Coma {
@@ -58,72 +232,144 @@ impl Parser<'_> {
 	fn primary(&mut self) -> Node {
 		match self.current_token.kind {
 			TokenKind::Integer => {
-				let value = self.current_token.value.parse::<i64>().unwrap(); // TODO: Check it
+				// Validated by the lexer
+				let value = self.current_token.value.parse::<i64>().unwrap();
 
 				self.advance();
 
-				return Node::Int(value);
+				Node::Int(value)
 			},
 			TokenKind::Float => {
-				let value = self.current_token.value.parse::<f64>().unwrap(); // TODO: Check it
+				let value = self.current_token.value.parse::<f64>().unwrap();
 
 				self.advance();
 
-				return Node::Float(value);
+				Node::Float(value)
 			},
 			TokenKind::Bool => {
 				let value = to_bool(&self.current_token.value);
 
 				self.advance();
 
-				return Node::Bool(value);
+				Node::Bool(value)
+			},
+			TokenKind::String => {
+				let value = self.current_token.value.clone();
+
+				self.advance();
+
+				Node::String(value)
 			},
 			TokenKind::Identifier => {
 				let name = self.current_token.value.clone();
+				let span = self.span();
 
 				self.advance();
 
+				if self.expect(TokenKind::LBracket) && self.struct_literal_allowed {
+					return self.struct_literal(name, span);
+				}
+
+				if self.expect(TokenKind::DoubleColon) {
+					return self.enum_variant(name, span);
+				}
+
 				if self.current_token.kind != TokenKind::LParenthesis {
-					return Node::Identifier(name);
+					return Node::Identifier { name, span };
 				}
 
 				self.advance();
 
-				let args = self.parse_args();
+				let args = self.parse_args(TokenKind::RParenthesis);
 
 				self.eat(TokenKind::RParenthesis);
 
 				Node::FunctionCall { 
-					name: name,
-					args: args,
+					name,
+					args,
+					span
 				}
 			},
 			TokenKind::LParenthesis => {
 				self.advance();
 
+				let struct_literal_allowed = self.struct_literal_allowed;
+				self.struct_literal_allowed = true;
+
 				let exp = self.parse_expression();
 
+				self.struct_literal_allowed = struct_literal_allowed;
+
 				self.eat(TokenKind::RParenthesis);
 
-				return exp;
+				exp
+			},
+			TokenKind::LSquareBracket => {
+				let span = self.span();
+				self.advance();
+
+				let elements = self.parse_args(TokenKind::RSquareBracket);
+
+				self.eat(TokenKind::RSquareBracket);
+
+				Node::Array {
+					elements,
+					span
+				}
+			},
+			TokenKind::Match => self.parse_match(),
+			TokenKind::If => self.parse_if_expression(),
+			TokenKind::None => {
+				self.advance();
+
+				Node::None
+			},
+			TokenKind::Some => {
+				self.advance();
+
+				Node::Some(Box::new(self.parenthesized_expression()))
+			},
+			TokenKind::Ok => {
+				self.advance();
+
+				Node::Ok(Box::new(self.parenthesized_expression()))
 			},
+			TokenKind::Err => {
+				self.advance();
+
+				Node::Err(Box::new(self.parenthesized_expression()))
+			},
+			TokenKind::Map => self.map_literal(),
 			TokenKind::Operator(op) => {
+				let span = self.span();
+
 				match op {
 					Operator::Not => {
 						self.advance();
 
-						return Node::UnaryOp { 
+						Node::UnaryOp { 
 							op: Operator::Not,
-							right: Box::new(self.primary())
-						};
+							right: Box::new(self.access()),
+							span
+						}
 					},
 					Operator::Minus => {
 						self.advance();
 
-						return Node::UnaryOp { 
+						Node::UnaryOp { 
 							op: Operator::Minus,
-							right: Box::new(self.primary())
-						};
+							right: Box::new(self.access()),
+							span
+						}
+					},
+					Operator::BitwiseNot => {
+						self.advance();
+
+						Node::UnaryOp { 
+							op: Operator::BitwiseNot,
+							right: Box::new(self.access()),
+							span
+						}
 					}
 					_ => panic!("No valid primary op")
 				}
@@ -134,258 +380,1555 @@ impl Parser<'_> {
 		}
 	}
 
-	fn parse_expression(&mut self) -> Node {
-		let lhs = self.primary();
-		
-		self.expression(lhs, 0)
+	fn parse_index(&mut self) -> Node {
+		self.eat(TokenKind::LSquareBracket);
+
+		let index = self.parse_expression();
+
+		self.eat(TokenKind::RSquareBracket);
+
+		index
 	}
 
-	fn expression(&mut self, mut lhs: Node, precedence: i16) -> Node {
-		
-		while let TokenKind::Operator(op) = self.current_token.kind {
-			if op.precedence() < precedence {
-				break;
-			}
+	fn parse_field(&mut self) -> String {
+		self.eat(TokenKind::Dot);
+
+		let field = self.current_token.value.clone();
+		self.eat(TokenKind::Identifier);
+
+		field
+	}
+
+	// `target op= value` is read as `target = target op value`
+	fn parse_assigned_value(&mut self, target: Node) -> Node {
+		if let TokenKind::CompoundAssign(op) = self.current_token.kind {
+			let span = self.span();
 			self.advance();
 
-			let mut rhs = self.primary();
-			while let TokenKind::Operator(lookahead) = self.current_token.kind {
-				if lookahead.precedence() > op.precedence() {
-					rhs = self.expression(rhs, op.precedence() + 1);
-				}
-				else {
-					break;
-				}
-			}
+			return Node::BinaryOp {
+				op,
+				left: Box::new(target),
+				right: Box::new(self.parse_expression()),
+				span
+			};
+		}
 
-			lhs = Node::BinaryOp { 
-				op: op,
-				left: Box::new(lhs), 
-				right: Box::new(rhs)
+		let step = match self.current_token.kind {
+			TokenKind::Increment => Some(Operator::Add),
+			TokenKind::Decrement => Some(Operator::Minus),
+			_ => None
+		};
+		if let Some(op) = step {
+			let span = self.span();
+			self.advance();
+
+			return Node::BinaryOp {
+				op,
+				left: Box::new(target),
+				right: Box::new(Node::Int(1)),
+				span
 			};
 		}
 
-		lhs
+		self.eat(TokenKind::Assign);
+
+		self.parse_expression()
 	}
 
-	fn instr(&mut self) -> Node {
-		match self.current_token.kind {
-			TokenKind::Let => {
-				self.eat(TokenKind::Let);
-				
-				let name = self.current_token.value.clone();
+	fn struct_literal(&mut self, name: String, span: Span) -> Node {
+		self.eat(TokenKind::LBracket);
 
-				self.eat(TokenKind::Identifier);
+		let mut field_names = Vec::new();
+		let mut values = Vec::new();
+		while self.expect(TokenKind::Identifier) {
+			field_names.push(self.current_token.value.clone());
+			self.advance();
 
-				let mut declared_type = None;
-				if self.expect(TokenKind::Colon) {
-					self.advance();
+			self.eat(TokenKind::Colon);
 
-					match self.current_token.kind {
-						TokenKind::Type(explicit_type) => {
-							declared_type =	Some(explicit_type);
-						},
-						_ => panic!("Not a valid type") // TODO: Better error handling
-					}
+			values.push(self.parse_expression());
 
-					self.advance();
-				}
+			if !self.expect(TokenKind::Coma) {
+				break;
+			}
+			self.advance();
+		}
 
-				self.eat(TokenKind::Assign);
+		self.eat(TokenKind::RBracket);
 
-				let value = self.parse_expression();
+		Node::StructLiteral {
+			name,
+			field_names,
+			values,
+			span
+		}
+	}
 
-				self.eat(TokenKind::Semilicon);
+	fn map_literal(&mut self) -> Node {
+		let span = self.span();
+		self.eat(TokenKind::Map);
+		self.eat(TokenKind::LBracket);
 
-				Node::VarDeclaration { 
-					name: name,
-					declared_type: declared_type,
-					value: Box::new(value)
-				}
-			},
-			TokenKind::Identifier => {
-				let name = self.current_token.value.clone();
-				self.advance();
+		let struct_literal_allowed = self.struct_literal_allowed;
+		self.struct_literal_allowed = true;
 
-				match self.current_token.kind {
-					TokenKind::Assign => {
-						self.advance();
+		let mut keys = Vec::new();
+		let mut values = Vec::new();
+		while !self.expect(TokenKind::RBracket) {
+			keys.push(self.parse_expression());
 
-						let value = self.parse_expression();
+			self.eat(TokenKind::Colon);
 
-						self.eat(TokenKind::Semilicon);
+			values.push(self.parse_expression());
 
-						Node::VarAssignation { 
-							name: name,
-							value: Box::new(value)
-						}
-					},
-					TokenKind::LParenthesis => {
-						self.advance();
+			if !self.expect(TokenKind::Coma) {
+				break;
+			}
+			self.advance();
+		}
 
-						let args = self.parse_args();
+		self.struct_literal_allowed = struct_literal_allowed;
 
-						self.eat(TokenKind::RParenthesis);
-						self.eat(TokenKind::Semilicon);
+		self.eat(TokenKind::RBracket);
 
-						Node::FunctionCall { 
-							name: name,
-							args: args,
-						}
-					},
-					_ => panic!("Wront kind after Identifier")
-				}				
-			},
-			TokenKind::Return => {
-				self.advance();
+		Node::Map {
+			keys,
+			values,
+			span
+		}
+	}
 
-				match self.current_token.kind {
-					TokenKind::Semilicon => {
-						self.advance();
+	fn enum_variant(&mut self, enum_name: String, span: Span) -> Node {
+		self.eat(TokenKind::DoubleColon);
 
-						Node::ReturnStatement { value: Box::new(None) }
-					},
-					_ => {
-						let value = self.parse_expression();
+		let variant = self.current_token.value.clone();
+		self.eat(TokenKind::Identifier);
 
-						self.eat(TokenKind::Semilicon);
+		let mut args = Vec::new();
+		if self.expect(TokenKind::LParenthesis) {
+			self.advance();
 
-						Node::ReturnStatement { value: Box::new(Some(value)) }
-					},
-				}
-			},
-			TokenKind::If => {
-				self.advance();
+			args = self.parse_args(TokenKind::RParenthesis);
 
-				let value = self.parse_expression();
+			self.eat(TokenKind::RParenthesis);
+		}
 
-				self.eat(TokenKind::LBracket);
+		Node::EnumVariant {
+			enum_name,
+			variant,
+			args,
+			span
+		}
+	}
 
-				let body = self.list_instr();
+	fn parse_pattern(&mut self) -> Pattern {
+		match self.current_token.kind {
+			TokenKind::Identifier => {
+				let name = self.current_token.value.clone();
+				self.advance();
 
-				self.eat(TokenKind::RBracket);
+				if !self.expect(TokenKind::DoubleColon) {
+					if name == "_" {
+						return Pattern::Wildcard;
+					}
 
-				Node::IfStatement { 
-					condition: Box::new(value), 
-					body: Box::new(body)
+					return Pattern::Binding(name);
 				}
-			}
-			TokenKind::Fn => {
+
 				self.advance();
 
-				let name = self.current_token.value.clone();
+				let variant = self.current_token.value.clone();
 				self.eat(TokenKind::Identifier);
-			
-				self.eat(TokenKind::LParenthesis);
 
-				let mut param_names : Vec<String> = Vec::new();
-				let mut param_types : Vec<Type> = Vec::new();
-				if self.current_token.kind == TokenKind::Identifier {
-					param_names.push(self.current_token.value.clone());
+				let mut fields = Vec::new();
+				if self.expect(TokenKind::LParenthesis) {
 					self.advance();
 
-					self.eat(TokenKind::Colon);
+					if !self.expect(TokenKind::RParenthesis) {
+						fields.push(self.parse_pattern());
 
-					match self.current_token.kind {
-						TokenKind::Type(declared_type) => {
-							param_types.push(declared_type);
+						while self.expect(TokenKind::Coma) {
 							self.advance();
+
+							fields.push(self.parse_pattern());
 						}
-						_ => panic!("No type declared") // TODO: Better error handling
 					}
 
-					while self.current_token.kind == TokenKind::Coma {
-						self.advance();
-
-						param_names.push(self.current_token.value.clone());
-						self.eat(TokenKind::Identifier);
+					self.eat(TokenKind::RParenthesis);
+				}
 
-						self.eat(TokenKind::Colon);
+				Pattern::Variant {
+					enum_name: name,
+					variant,
+					fields
+				}
+			},
+			TokenKind::Integer => {
+				let value = self.current_token.value.parse::<i64>().unwrap();
+				self.advance();
+
+				Pattern::Int(value)
+			},
+			TokenKind::Operator(Operator::Minus) => {
+				self.advance();
+
+				if !self.expect(TokenKind::Integer) {
+					panic!("Not a valid pattern"); // TODO: Better error handling
+				}
+
+				let value = self.current_token.value.parse::<i64>().unwrap();
+				self.advance();
+
+				Pattern::Int(-value)
+			},
+			TokenKind::Bool => {
+				let value = to_bool(&self.current_token.value);
+				self.advance();
+
+				Pattern::Bool(value)
+			},
+			TokenKind::None => {
+				self.advance();
+
+				Pattern::None
+			},
+			TokenKind::Some => {
+				self.advance();
+
+				Pattern::Some(Box::new(self.parenthesized_pattern()))
+			},
+			TokenKind::Ok => {
+				self.advance();
+
+				Pattern::Ok(Box::new(self.parenthesized_pattern()))
+			},
+			TokenKind::Err => {
+				self.advance();
+
+				Pattern::Err(Box::new(self.parenthesized_pattern()))
+			},
+			_ => panic!("Not a valid pattern") // TODO: Better error handling
+		}
+	}
+
+	fn parse_match(&mut self) -> Node {
+		let span = self.span();
+		self.eat(TokenKind::Match);
+
+		let struct_literal_allowed = self.struct_literal_allowed;
+		self.struct_literal_allowed = false;
+		let value = self.parse_expression();
+		self.struct_literal_allowed = true;
+
+		self.eat(TokenKind::LBracket);
+
+		let mut patterns = Vec::new();
+		let mut arms = Vec::new();
+		while !self.expect(TokenKind::RBracket) {
+			patterns.push(self.parse_pattern());
+
+			self.eat(TokenKind::FatArrow);
+
+			arms.push(self.parse_expression());
+
+			if !self.expect(TokenKind::Coma) {
+				break;
+			}
+			self.advance();
+		}
+
+		self.eat(TokenKind::RBracket);
+
+		self.struct_literal_allowed = struct_literal_allowed;
+
+		Node::Match {
+			value: Box::new(value),
+			patterns,
+			arms,
+			span
+		}
+	}
+
+	fn parse_if_expression(&mut self) -> Node {
+		let span = self.span();
+		self.eat(TokenKind::If);
+
+		let struct_literal_allowed = self.struct_literal_allowed;
+		self.struct_literal_allowed = false;
+		let condition = self.parse_expression();
+		self.struct_literal_allowed = true;
+
+		let then_branch = self.parse_block();
+
+		self.eat(TokenKind::Else);
+		let else_branch = match self.current_token.kind {
+			TokenKind::If => self.parse_if_expression(),
+			_ => self.parse_block()
+		};
+
+		self.struct_literal_allowed = struct_literal_allowed;
+
+		Node::IfExpression {
+			condition: Box::new(condition),
+			then_branch: Box::new(then_branch),
+			else_branch: Box::new(else_branch),
+			span
+		}
+	}
+
+	// `{ instructions value }`, an `if` starting an instruction is a statement, not the value
+	fn parse_block(&mut self) -> Node {
+		self.eat(TokenKind::LBracket);
+
+		let mut instructions = Vec::new();
+		let value = loop {
+			match self.current_token.kind {
+				TokenKind::Let | TokenKind::Const | TokenKind::If | TokenKind::For | TokenKind::Fn | TokenKind::Return | TokenKind::Struct | TokenKind::Enum => instructions.push(self.instr()),
+				_ => {
+					// Assignations and calls are only told apart from the value once their target is read
+					let span = self.span();
+					let expression = self.parse_expression();
+
+					match self.current_token.kind {
+						TokenKind::Assign | TokenKind::CompoundAssign(_) | TokenKind::Increment | TokenKind::Decrement => instructions.push(self.block_assignation(expression, span)),
+						TokenKind::Semilicon if matches!(expression, Node::FunctionCall { .. }) => {
+							self.advance();
+							instructions.push(expression);
+						},
+						_ => break expression
+					}
+				}
+			}
+		};
+
+		self.eat(TokenKind::RBracket);
+
+		let body = instructions.into_iter().rev().fold(None, |next, current| Some(Node::InstructionList {
+			current: Box::new(current),
+			next: Box::new(next)
+		}));
+
+		Node::Block {
+			body: Box::new(body),
+			value: Box::new(value)
+		}
+	}
+
+	fn block_assignation(&mut self, target: Node, span: Span) -> Node {
+		let mut path = Vec::new();
+		let mut root = &target;
+		let name = loop {
+			match root {
+				Node::Identifier { name, .. } => break name.clone(),
+				Node::Index { array, index, .. } => {
+					path.push(Accessor::Index(index.as_ref().clone()));
+					root = array.as_ref();
+				},
+				Node::FieldAccess { value, field, .. } => {
+					path.push(Accessor::Field(field.clone()));
+					root = value.as_ref();
+				},
+				_ => panic!("Can't assign to {:?}", target)
+			}
+		};
+		path.reverse();
+
+		let value = self.parse_assigned_value(target.clone());
+		self.eat(TokenKind::Semilicon);
+
+		if path.is_empty() {
+			Node::VarAssignation { name, value: Box::new(value), span }
+		}
+		else {
+			Node::ElementAssignation { name, path, value: Box::new(value), span }
+		}
+	}
+
+	fn access(&mut self) -> Node {
+		let mut node = self.primary();
+
+		loop {
+			let span = self.span();
+
+			node = match self.current_token.kind {
+				TokenKind::LSquareBracket => Node::Index {
+					array: Box::new(node),
+					index: Box::new(self.parse_index()),
+					span
+				},
+				TokenKind::Dot => Node::FieldAccess {
+					value: Box::new(node),
+					field: self.parse_field(),
+					span
+				},
+				TokenKind::QuestionMark => {
+					self.advance();
+
+					Node::Try {
+						value: Box::new(node),
+						span
+					}
+				},
+				_ => return node
+			};
+		}
+	}
+
+	fn postfix(&mut self) -> Node {
+		let mut node = self.access();
+
+		while self.expect(TokenKind::As) {
+			let span = self.span();
+			self.advance();
+
+			node = Node::Cast {
+				value: Box::new(node),
+				target: self.parse_type(),
+				span
+			};
+		}
+
+		node
+	}
+
+	fn parse_expression(&mut self) -> Node {
+		let lhs = self.postfix();
+		
+		self.expression(lhs, 0)
+	}
+
+	fn expression(&mut self, mut lhs: Node, precedence: i16) -> Node {
+		
+		while let TokenKind::Operator(op) = self.current_token.kind {
+			if op.precedence() < precedence {
+				break;
+			}
+			let span = self.span();
+			self.advance();
+
+			let mut rhs = self.postfix();
+			while let TokenKind::Operator(lookahead) = self.current_token.kind {
+				if lookahead.precedence() > op.precedence() {
+					rhs = self.expression(rhs, op.precedence() + 1);
+				}
+				else if lookahead.precedence() == op.precedence() && lookahead.is_right_associative() {
+					rhs = self.expression(rhs, op.precedence());
+				}
+				else {
+					break;
+				}
+			}
+
+			lhs = Node::BinaryOp { 
+				op,
+				left: Box::new(lhs), 
+				right: Box::new(rhs),
+				span
+			};
+		}
+
+		lhs
+	}
+
+	fn instr(&mut self) -> Node {
+		match self.current_token.kind {
+			TokenKind::Let => {
+				let span = self.span();
+				self.eat(TokenKind::Let);
+
+				let mutable = self.expect(TokenKind::Mut);
+				if mutable {
+					self.advance();
+				}
+				
+				let name = self.current_token.value.clone();
+
+				self.eat(TokenKind::Identifier);
+
+				let mut declared_type = None;
+				if self.expect(TokenKind::Colon) {
+					self.advance();
+
+					declared_type = Some(self.parse_type());
+				}
+
+				self.eat(TokenKind::Assign);
+
+				let value = self.parse_expression();
+
+				self.eat(TokenKind::Semilicon);
+
+				Node::VarDeclaration { 
+					name,
+					mutable,
+					declared_type,
+					value: Box::new(value),
+					span
+				}
+			},
+			TokenKind::Const => {
+				let span = self.span();
+				self.advance();
+
+				let name = self.current_token.value.clone();
+				self.eat(TokenKind::Identifier);
+
+				self.eat(TokenKind::Colon);
+				let declared_type = self.parse_type();
+
+				self.eat(TokenKind::Assign);
+
+				let value = self.parse_expression();
+
+				self.eat(TokenKind::Semilicon);
+
+				Node::ConstDeclaration {
+					name,
+					declared_type,
+					value: Box::new(value),
+					span
+				}
+			},
+			TokenKind::Identifier => {
+				let name = self.current_token.value.clone();
+				let span = self.span();
+				self.advance();
+
+				match self.current_token.kind {
+					TokenKind::Assign | TokenKind::CompoundAssign(_) | TokenKind::Increment | TokenKind::Decrement => {
+						let value = self.parse_assigned_value(Node::Identifier { name: name.clone(), span });
+
+						self.eat(TokenKind::Semilicon);
+
+						Node::VarAssignation { 
+							name,
+							value: Box::new(value),
+							span
+						}
+					},
+					TokenKind::LSquareBracket | TokenKind::Dot => {
+						let mut path = Vec::new();
+						// Read value of the element, for a compound assignation
+						let mut target = Node::Identifier { name: name.clone(), span };
+						loop {
+							match self.current_token.kind {
+								TokenKind::LSquareBracket => {
+									let index = self.parse_index();
+									target = Node::Index { array: Box::new(target), index: Box::new(index.clone()), span };
+									path.push(Accessor::Index(index));
+								},
+								TokenKind::Dot => {
+									let field = self.parse_field();
+									target = Node::FieldAccess { value: Box::new(target), field: field.clone(), span };
+									path.push(Accessor::Field(field));
+								},
+								_ => break
+							}
+						}
+
+						let value = self.parse_assigned_value(target);
+
+						self.eat(TokenKind::Semilicon);
+
+						Node::ElementAssignation { 
+							name,
+							path,
+							value: Box::new(value),
+							span
+						}
+					},
+					TokenKind::LParenthesis => {
+						self.advance();
+
+						let args = self.parse_args(TokenKind::RParenthesis);
+
+						self.eat(TokenKind::RParenthesis);
+						self.eat(TokenKind::Semilicon);
+
+						Node::FunctionCall { 
+							name,
+							args,
+							span
+						}
+					},
+					_ => panic!("Wront kind after Identifier")
+				}				
+			},
+			TokenKind::Return => {
+				let span = self.span();
+				self.advance();
+
+				match self.current_token.kind {
+					TokenKind::Semilicon => {
+						self.advance();
+
+						Node::ReturnStatement { value: Box::new(None), span }
+					},
+					_ => {
+						let value = self.parse_expression();
+
+						self.eat(TokenKind::Semilicon);
+
+						Node::ReturnStatement { value: Box::new(Some(value)), span }
+					},
+				}
+			},
+			TokenKind::If => {
+				let span = self.span();
+				self.advance();
+
+				if self.expect(TokenKind::Let) {
+					self.advance();
+
+					let pattern = self.parse_pattern();
+
+					self.eat(TokenKind::Assign);
+
+					self.struct_literal_allowed = false;
+					let value = self.parse_expression();
+					self.struct_literal_allowed = true;
+
+					self.eat(TokenKind::LBracket);
+
+					let body = self.list_instr();
+
+					self.eat(TokenKind::RBracket);
+
+					return Node::IfLet { 
+						pattern,
+						value: Box::new(value), 
+						body: Box::new(body),
+						span
+					};
+				}
+
+				self.struct_literal_allowed = false;
+				let value = self.parse_expression();
+				self.struct_literal_allowed = true;
+
+				self.eat(TokenKind::LBracket);
+
+				let body = self.list_instr();
+
+				self.eat(TokenKind::RBracket);
+
+				let mut else_body = None;
+				if self.expect(TokenKind::Else) {
+					self.advance();
+
+					else_body = match self.current_token.kind {
+						TokenKind::If => Some(Node::InstructionList {
+							current: Box::new(self.instr()),
+							next: Box::new(None)
+						}),
+						_ => {
+							self.eat(TokenKind::LBracket);
+							let else_body = self.list_instr();
+							self.eat(TokenKind::RBracket);
+
+							else_body
+						}
+					};
+				}
+
+				Node::IfStatement { 
+					condition: Box::new(value), 
+					body: Box::new(body),
+					else_body: Box::new(else_body),
+					span
+				}
+			}
+			TokenKind::For => {
+				let span = self.span();
+				self.advance();
+
+				let variable = self.current_token.value.clone();
+				self.eat(TokenKind::Identifier);
+
+				self.eat(TokenKind::In);
+
+				self.struct_literal_allowed = false;
+				let iterable = self.parse_expression();
+				self.struct_literal_allowed = true;
+
+				self.eat(TokenKind::LBracket);
+
+				let body = self.list_instr();
+
+				self.eat(TokenKind::RBracket);
+
+				Node::ForLoop {
+					variable,
+					iterable: Box::new(iterable),
+					body: Box::new(body),
+					span
+				}
+			}
+			TokenKind::Fn => {
+				self.advance();
+
+				let name = self.current_token.value.clone();
+				let span = self.span();
+				self.eat(TokenKind::Identifier);
+
+				let type_params = self.parse_type_params();
+			
+				self.eat(TokenKind::LParenthesis);
+
+				let mut param_names : Vec<String> = Vec::new();
+				let mut param_spans : Vec<Span> = Vec::new();
+				let mut param_types : Vec<Type> = Vec::new();
+				if self.current_token.kind == TokenKind::Identifier {
+					param_names.push(self.current_token.value.clone());
+					param_spans.push(self.span());
+					self.advance();
+
+					param_types.push(self.parse_param_type());
+
+					while self.current_token.kind == TokenKind::Coma {
+						self.advance();
+
+						param_names.push(self.current_token.value.clone());
+						param_spans.push(self.span());
+						self.eat(TokenKind::Identifier);
+
+						param_types.push(self.parse_param_type());
+					}
+				}
+
+				self.eat(TokenKind::RParenthesis);
+
+				// Like parameter types, inferred from the return statements
+				let mut return_type = Type::Infer;
+				if self.expect(TokenKind::Arrow) {
+					self.advance();
+
+					return_type = self.parse_type();
+				}
+
+				self.eat(TokenKind::LBracket);
+
+				let body = self.list_instr();
+
+				self.eat(TokenKind::RBracket);
+
+				Node::FunctionDeclaration { 
+					name, 
+					type_params,
+					param_names, 
+					param_spans,
+					param_types,
+					return_type,
+					body: Box::new(body),
+					span
+				}
+			}
+			TokenKind::Struct => {
+				self.advance();
+
+				let name = self.current_token.value.clone();
+				let span = self.span();
+				self.eat(TokenKind::Identifier);
+
+				self.eat(TokenKind::LBracket);
+
+				let mut field_names : Vec<String> = Vec::new();
+				let mut field_types : Vec<Type> = Vec::new();
+				while self.expect(TokenKind::Identifier) {
+					field_names.push(self.current_token.value.clone());
+					self.advance();
+
+					self.eat(TokenKind::Colon);
+
+					field_types.push(self.parse_type());
+
+					if !self.expect(TokenKind::Coma) {
+						break;
+					}
+					self.advance();
+				}
+
+				self.eat(TokenKind::RBracket);
+
+				Node::StructDeclaration { 
+					name,
+					field_names,
+					field_types,
+					span
+				}
+			}
+			TokenKind::Enum => {
+				self.advance();
+
+				let name = self.current_token.value.clone();
+				let span = self.span();
+				self.eat(TokenKind::Identifier);
+
+				self.eat(TokenKind::LBracket);
+
+				let mut variant_names : Vec<String> = Vec::new();
+				let mut variant_types : Vec<Vec<Type>> = Vec::new();
+				while self.expect(TokenKind::Identifier) {
+					variant_names.push(self.current_token.value.clone());
+					self.advance();
+
+					let mut field_types = Vec::new();
+					if self.expect(TokenKind::LParenthesis) {
+						self.advance();
+
+						if !self.expect(TokenKind::RParenthesis) {
+							field_types.push(self.parse_type());
+
+							while self.expect(TokenKind::Coma) {
+								self.advance();
+
+								field_types.push(self.parse_type());
+							}
+						}
+
+						self.eat(TokenKind::RParenthesis);
+					}
+					variant_types.push(field_types);
+
+					if !self.expect(TokenKind::Coma) {
+						break;
+					}
+					self.advance();
+				}
+
+				self.eat(TokenKind::RBracket);
+
+				Node::EnumDeclaration { 
+					name,
+					variant_names,
+					variant_types,
+					span
+				}
+			}
+			_ => { panic!("instr : no valid token kind {:?}", self.current_token); }
+		}
+	}
+
+	fn list_instr(&mut self) -> Option<Node> {
+		if self.expect(TokenKind::Eof) || self.expect(TokenKind::RBracket) {
+			return None;
+		}
+
+		//TODO: May not be managed by parser (see CFG)
+		if self.expect(TokenKind::Return) {
+			let result = Node::InstructionList { 
+				current: Box::new(self.instr()),
+				next: Box::new(None) 
+			};
+
+			while !(self.expect(TokenKind::Eof) || self.expect(TokenKind::RBracket)){
+				self.advance();
+			}
+
+			return Some(result);
+		}
+
+		Some(Node::InstructionList { 
+			current: Box::new(self.instr()),
+			next: Box::new(self.list_instr()) 
+		})
+	}
+
+	fn prgm(&mut self) -> Option<Node> {
+		self.list_instr()
+	}
+
+	pub fn ast(&mut self) -> Option<Node> {
+		self.prgm()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]	
+	fn condition_parsing(){
+		let mut lexer = Lexer::new("let condition = 2 == 2 || 3.5 != 3.6;");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("condition"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(
+						Node::BinaryOp { 
+							op: Operator::LogicalOr,
+							left: Box::new(
+								Node::BinaryOp { 
+									op: Operator::Equal, 
+									left: Box::new(Node::Int(2)), 
+									right: Box::new(Node::Int(2)),
+									span: Span::new(0, 18)
+								}
+							),
+							right: Box::new(
+								Node::BinaryOp { 
+									op: Operator::NotEqual, 
+									left: Box::new(Node::Float(3.5)), 
+									right: Box::new(Node::Float(3.6)),
+									span: Span::new(0, 30)
+								}
+							),
+							span: Span::new(0, 23)
+						}
+					),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
 
-						match self.current_token.kind {
-							TokenKind::Type(declared_type) => {
-								param_types.push(declared_type);
-								self.advance();
+	#[test]
+	fn if_statement_parsing(){
+		let mut lexer = Lexer::new("if condition == 2 { let test = 3; }");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::IfStatement { 
+					condition: Box::new(Node::BinaryOp { 
+						op: Operator::Equal, 
+						left: Box::new(
+							Node::Identifier { name: String::from("condition"), span: Span::new(0, 3) }
+						), 
+						right: Box::new(
+							Node::Int(2)
+						),
+						span: Span::new(0, 13)
+					}),
+					body: Box::new(Some(Node::InstructionList { 
+						current: Box::new(
+							Node::VarDeclaration { 
+								name: String::from("test"),
+								mutable: false,
+								declared_type: None,
+								value: Box::new(Node::Int(3)),
+								span: Span::new(0, 20)
 							}
-							_ => panic!("No type declared") // TODO: Better error handling
+						), 
+						next: Box::new(None) 
+					})),
+					else_body: Box::new(None),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn if_else_statement_parsing(){
+		let mut lexer = Lexer::new("if a { x = 1; } else if b { x = 2; } else { x = 3; }");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::IfStatement {
+					condition: Box::new(Node::Identifier { name: String::from("a"), span: Span::new(0, 3) }),
+					body: Box::new(Some(Node::InstructionList {
+						current: Box::new(Node::VarAssignation {
+							name: String::from("x"),
+							value: Box::new(Node::Int(1)),
+							span: Span::new(0, 7)
+						}),
+						next: Box::new(None)
+					})),
+					else_body: Box::new(Some(Node::InstructionList {
+						current: Box::new(Node::IfStatement {
+							condition: Box::new(Node::Identifier { name: String::from("b"), span: Span::new(0, 24) }),
+							body: Box::new(Some(Node::InstructionList {
+								current: Box::new(Node::VarAssignation {
+									name: String::from("x"),
+									value: Box::new(Node::Int(2)),
+									span: Span::new(0, 28)
+								}),
+								next: Box::new(None)
+							})),
+							else_body: Box::new(Some(Node::InstructionList {
+								current: Box::new(Node::VarAssignation {
+									name: String::from("x"),
+									value: Box::new(Node::Int(3)),
+									span: Span::new(0, 44)
+								}),
+								next: Box::new(None)
+							})),
+							span: Span::new(0, 21)
+						}),
+						next: Box::new(None)
+					})),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn for_loop_parsing(){
+		let mut lexer = Lexer::new("for value in values { show(value); }");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::ForLoop {
+					variable: String::from("value"),
+					iterable: Box::new(Node::Identifier { name: String::from("values"), span: Span::new(0, 13) }),
+					body: Box::new(Some(Node::InstructionList {
+						current: Box::new(Node::FunctionCall {
+							name: String::from("show"),
+							args: Vec::from([Node::Identifier { name: String::from("value"), span: Span::new(0, 27) }]),
+							span: Span::new(0, 22)
+						}),
+						next: Box::new(None)
+					})),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn function_declaration_parsing(){
+		let mut lexer = Lexer::new("fn foo(arg1: int, arg2: int, arg3: int) -> int { return 2; let test = 2; }");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::FunctionDeclaration { 
+					name: String::from("foo"), 
+					type_params: Vec::new(),
+					param_names: Vec::from([String::from("arg1"), String::from("arg2"), String::from("arg3")]),
+					param_spans: Vec::from([Span::new(0, 7), Span::new(0, 18), Span::new(0, 29)]),
+					param_types: Vec::from([Type::Int, Type::Int, Type::Int]),
+					return_type: Type::Int,
+					body: Box::new(Some(Node::InstructionList { 
+						current: Box::new(Node::ReturnStatement { 
+							value: Box::new(Some(Node::Int(2))),
+							span: Span::new(0, 49)
+						}),
+						next: Box::new(None) 
+					})),
+					span: Span::new(0, 3)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn generic_function_parsing(){
+		let mut lexer = Lexer::new("fn pick<T: Ord + Num, U>(a: T, b: U) -> option<T> { }");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::FunctionDeclaration { 
+					name: String::from("pick"), 
+					type_params: Vec::from([
+						TypeParam { name: String::from("T"), bounds: Vec::from([String::from("Ord"), String::from("Num")]) },
+						TypeParam { name: String::from("U"), bounds: Vec::new() }
+					]),
+					param_names: Vec::from([String::from("a"), String::from("b")]),
+					param_spans: Vec::from([Span::new(0, 25), Span::new(0, 31)]),
+					param_types: Vec::from([Type::Named(String::from("T")), Type::Named(String::from("U"))]),
+					return_type: Type::Option(Box::new(Type::Named(String::from("T")))),
+					body: Box::new(None),
+					span: Span::new(0, 3)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn unannotated_function_parsing(){
+		let mut lexer = Lexer::new("fn add(a, b: int) { }");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::FunctionDeclaration {
+					name: String::from("add"),
+					type_params: Vec::new(),
+					param_names: Vec::from([String::from("a"), String::from("b")]),
+					param_spans: Vec::from([Span::new(0, 7), Span::new(0, 10)]),
+					param_types: Vec::from([Type::Infer, Type::Int]),
+					return_type: Type::Infer,
+					body: Box::new(None),
+					span: Span::new(0, 3)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]
+	fn function_call_parsing(){
+		let mut lexer = Lexer::new("foo(arg1, arg2 + 2, arg3);");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::FunctionCall { 
+					name: String::from("foo"),
+					args: Vec::from([
+						Node::Identifier { name: String::from("arg1"), span: Span::new(0, 4) },
+						Node::BinaryOp { 
+							op: Operator::Add, 
+							left: Box::new(Node::Identifier { name: String::from("arg2"), span: Span::new(0, 10) }), 
+							right: Box::new(Node::Int(2)),
+							span: Span::new(0, 15)
+						},
+						Node::Identifier { name: String::from("arg3"), span: Span::new(0, 20) },
+					]),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]	
+	fn math_exp_parsing(){
+		let mut lexer = Lexer::new("let math: int = -1 * 3 + 4 * 2;");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("math"),
+					mutable: false,
+					declared_type: Some(Type::Int),
+					value: Box::new(
+						Node::BinaryOp { 
+							op: Operator::Add,
+							left: Box::new(
+								Node::BinaryOp { 
+									op: Operator::Product, 
+									left: Box::new(Node::UnaryOp {
+										op: Operator::Minus,
+										right: Box::new(Node::Int(1)),
+										span: Span::new(0, 16)
+									}), 
+									right: Box::new(Node::Int(3)),
+									span: Span::new(0, 19)
+								}
+							),
+							right: Box::new(
+								Node::BinaryOp { 
+									op: Operator::Product, 
+									left: Box::new(Node::Int(4)), 
+									right: Box::new(Node::Int(2)),
+									span: Span::new(0, 27)
+								}
+							),
+							span: Span::new(0, 23)
+						}
+					),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]	
+	fn power_right_associativity_parsing(){
+		let mut lexer = Lexer::new("let power = 2 ** 3 ** 2 % 5;");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("power"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(
+						Node::BinaryOp { 
+							op: Operator::Modulo,
+							left: Box::new(
+								Node::BinaryOp { 
+									op: Operator::Power, 
+									left: Box::new(Node::Int(2)), 
+									right: Box::new(Node::BinaryOp { 
+										op: Operator::Power, 
+										left: Box::new(Node::Int(3)), 
+										right: Box::new(Node::Int(2)),
+										span: Span::new(0, 19)
+									}),
+									span: Span::new(0, 14)
+								}
+							),
+							right: Box::new(Node::Int(5)),
+							span: Span::new(0, 24)
+						}
+					),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]	
+	fn bitwise_precedence_parsing(){
+		let mut lexer = Lexer::new("let bits = 1 | 2 & 3 << 1;");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("bits"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(
+						Node::BinaryOp { 
+							op: Operator::BitwiseOr,
+							left: Box::new(Node::Int(1)),
+							right: Box::new(
+								Node::BinaryOp { 
+									op: Operator::BitwiseAnd, 
+									left: Box::new(Node::Int(2)), 
+									right: Box::new(Node::BinaryOp { 
+										op: Operator::ShiftLeft, 
+										left: Box::new(Node::Int(3)), 
+										right: Box::new(Node::Int(1)),
+										span: Span::new(0, 21)
+									}),
+									span: Span::new(0, 17)
+								}
+							),
+							span: Span::new(0, 13)
+						}
+					),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
+
+	#[test]	
+	fn logical_precedence_parsing(){
+		let mut lexer = Lexer::new("let logic = a || b && c;");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("logic"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(
+						Node::BinaryOp { 
+							op: Operator::LogicalOr,
+							left: Box::new(Node::Identifier { name: String::from("a"), span: Span::new(0, 12) }),
+							right: Box::new(
+								Node::BinaryOp { 
+									op: Operator::LogicalAnd, 
+									left: Box::new(Node::Identifier { name: String::from("b"), span: Span::new(0, 17) }), 
+									right: Box::new(Node::Identifier { name: String::from("c"), span: Span::new(0, 22) }),
+									span: Span::new(0, 19)
+								}
+							),
+							span: Span::new(0, 14)
 						}
-					}
-				}
+					),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
 
-				self.eat(TokenKind::RParenthesis);
+	#[test]	
+	fn cast_parsing(){
+		let mut lexer = Lexer::new("let mixed = -count as float * 2.5;");
 
-				let mut return_type = Type::Void;
-				if self.expect(TokenKind::Arrow) {
-					self.advance();
+		let mut parser = Parser::new(&mut lexer);
 
-					match self.current_token.kind {
-						TokenKind::Type(declared_type) => {
-							return_type = declared_type;
-							self.advance();
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("mixed"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(
+						Node::BinaryOp { 
+							op: Operator::Product,
+							left: Box::new(Node::Cast {
+								value: Box::new(Node::UnaryOp {
+									op: Operator::Minus,
+									right: Box::new(Node::Identifier { name: String::from("count"), span: Span::new(0, 13) }),
+									span: Span::new(0, 12)
+								}),
+								target: Type::Float,
+								span: Span::new(0, 19)
+							}),
+							right: Box::new(Node::Float(2.5)),
+							span: Span::new(0, 28)
 						}
-						_ => panic!("No type declared") // TODO: Better error handling
-					}
-				}
+					),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(None)
+			}
+		));
+	}
 
-				self.eat(TokenKind::LBracket);
+	#[test]
+	fn comment_trivia_parsing(){
+		let mut lexer = Lexer::with_comments("// answer\nlet /* value */ answer = 42; /* end */");
 
-				let body = self.list_instr();
+		let mut parser = Parser::new(&mut lexer);
 
-				self.eat(TokenKind::RBracket);
+		let ast = parser.ast();
 
-				Node::FunctionDeclaration { 
-					name: name, 
-					param_names: param_names, 
-					param_types: param_types,
-					return_type: return_type,
-					body: Box::new(body),
-				}
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("answer"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::Int(42)),
+					span: Span::new(1, 0)
+				}),
+				next: Box::new(None)
 			}
-			_ => { panic!("instr : no valid token kind {:?}", self.current_token); }
-		}
+		));
 	}
 
-	fn list_instr(&mut self) -> Option<Node> {
-		if self.expect(TokenKind::Eof) || self.expect(TokenKind::RBracket) {
-			return None;
-		}
+	#[test]
+	fn array_parsing(){
+		let mut lexer = Lexer::new("let grid: [[int]] = [[1], []]; grid[0][1] = -grid[0][0];");
 
-		//TODO: May not be managed by parser (see CFG)
-		if self.expect(TokenKind::Return) {
-			let result = Node::InstructionList { 
-				current: Box::new(self.instr()),
-				next: Box::new(None) 
-			};
+		let mut parser = Parser::new(&mut lexer);
 
-			while !(self.expect(TokenKind::Eof) || self.expect(TokenKind::RBracket)){
-				self.advance();
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("grid"),
+					mutable: false,
+					declared_type: Some(Type::Array(Box::new(Type::Array(Box::new(Type::Int))))),
+					value: Box::new(Node::Array { elements: Vec::from([
+						Node::Array { elements: Vec::from([Node::Int(1)]), span: Span::new(0, 21) },
+						Node::Array { elements: Vec::new(), span: Span::new(0, 26) }
+					]), span: Span::new(0, 20) }),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::ElementAssignation {
+						name: String::from("grid"),
+						path: Vec::from([Accessor::Index(Node::Int(0)), Accessor::Index(Node::Int(1))]),
+						value: Box::new(Node::UnaryOp {
+							op: Operator::Minus,
+							right: Box::new(Node::Index {
+								array: Box::new(Node::Index {
+									array: Box::new(Node::Identifier { name: String::from("grid"), span: Span::new(0, 45) }),
+									index: Box::new(Node::Int(0)),
+									span: Span::new(0, 49)
+								}),
+								index: Box::new(Node::Int(0)),
+								span: Span::new(0, 52)
+							}),
+							span: Span::new(0, 44)
+						}),
+						span: Span::new(0, 31)
+					}),
+					next: Box::new(None)
+				}))
 			}
+		));
+	}
 
-			return Some(result);
-		}
+	#[test]
+	fn struct_parsing(){
+		let mut lexer = Lexer::new("struct Point { x: float, y: float } if p.x == 1.0 { p.y = (Point { x: 1.0, y: 2.0 }).y; }");
 
-		Some(Node::InstructionList { 
-			current: Box::new(self.instr()),
-			next: Box::new(self.list_instr()) 
-		})
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::StructDeclaration { 
+					name: String::from("Point"),
+					field_names: Vec::from([String::from("x"), String::from("y")]),
+					field_types: Vec::from([Type::Float, Type::Float]),
+					span: Span::new(0, 7)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::IfStatement {
+						condition: Box::new(Node::BinaryOp {
+							op: Operator::Equal,
+							left: Box::new(Node::FieldAccess {
+								value: Box::new(Node::Identifier { name: String::from("p"), span: Span::new(0, 39) }),
+								field: String::from("x"),
+								span: Span::new(0, 40)
+							}),
+							right: Box::new(Node::Float(1.0)),
+							span: Span::new(0, 43)
+						}),
+						body: Box::new(Some(Node::InstructionList {
+							current: Box::new(Node::ElementAssignation {
+								name: String::from("p"),
+								path: Vec::from([Accessor::Field(String::from("y"))]),
+								value: Box::new(Node::FieldAccess {
+									value: Box::new(Node::StructLiteral {
+										name: String::from("Point"),
+										field_names: Vec::from([String::from("x"), String::from("y")]),
+										values: Vec::from([Node::Float(1.0), Node::Float(2.0)]),
+										span: Span::new(0, 59)
+									}),
+									field: String::from("y"),
+									span: Span::new(0, 84)
+								}),
+								span: Span::new(0, 52)
+							}),
+							next: Box::new(None)
+						})),
+						else_body: Box::new(None),
+						span: Span::new(0, 36)
+					}),
+					next: Box::new(None)
+				}))
+			}
+		));
 	}
 
-	fn prgm(&mut self) -> Option<Node> {
-		self.list_instr()
+	#[test]
+	fn match_parsing(){
+		let mut lexer = Lexer::new("enum Shape { Circle(float), Empty } let area = match shape { Shape::Circle(r) => r, -1 => 0.0, _ => 1.0 };");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::EnumDeclaration { 
+					name: String::from("Shape"),
+					variant_names: Vec::from([String::from("Circle"), String::from("Empty")]),
+					variant_types: Vec::from([Vec::from([Type::Float]), Vec::new()]),
+					span: Span::new(0, 5)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::VarDeclaration {
+						name: String::from("area"),
+						mutable: false,
+						declared_type: None,
+						value: Box::new(Node::Match {
+							value: Box::new(Node::Identifier { name: String::from("shape"), span: Span::new(0, 53) }),
+							patterns: Vec::from([
+								Pattern::Variant {
+									enum_name: String::from("Shape"),
+									variant: String::from("Circle"),
+									fields: Vec::from([Pattern::Binding(String::from("r"))])
+								},
+								Pattern::Int(-1),
+								Pattern::Wildcard
+							]),
+							arms: Vec::from([Node::Identifier { name: String::from("r"), span: Span::new(0, 81) }, Node::Float(0.0), Node::Float(1.0)]),
+							span: Span::new(0, 47)
+						}),
+						span: Span::new(0, 36)
+					}),
+					next: Box::new(None)
+				}))
+			}
+		));
 	}
 
-	pub fn ast(&mut self) -> Option<Node> {
-		return self.prgm();
+	#[test]
+	fn option_parsing(){
+		let mut lexer = Lexer::new("let x: option<option<int>> = some(none); if let some(some(v)) = x { v = 1; }");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("x"),
+					mutable: false,
+					declared_type: Some(Type::Option(Box::new(Type::Option(Box::new(Type::Int))))),
+					value: Box::new(Node::Some(Box::new(Node::None))),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::IfLet {
+						pattern: Pattern::Some(Box::new(Pattern::Some(Box::new(Pattern::Binding(String::from("v")))))),
+						value: Box::new(Node::Identifier { name: String::from("x"), span: Span::new(0, 64) }),
+						body: Box::new(Some(Node::InstructionList {
+							current: Box::new(Node::VarAssignation {
+								name: String::from("v"),
+								value: Box::new(Node::Int(1)),
+								span: Span::new(0, 68)
+							}),
+							next: Box::new(None)
+						})),
+						span: Span::new(0, 41)
+					}),
+					next: Box::new(None)
+				}))
+			}
+		));
 	}
-}
 
-#[cfg(test)]
-mod tests {
-	use super::*;
-	
-	#[test]	
-	fn condition_parsing(){
-		let mut lexer = Lexer::new("let condition = 2 == 2 || 3.5 != 3.6;");
+	#[test]
+	fn mutable_declaration_parsing(){
+		let mut lexer = Lexer::new("let mut counter: int = 0; counter = 1;");
 
 		let mut parser = Parser::new(&mut lexer);
 
@@ -394,36 +1937,27 @@ mod tests {
 		assert_eq!(ast,Some(
 			Node::InstructionList {
 				current: Box::new(Node::VarDeclaration { 
-					name: String::from("condition"),
-					declared_type: None,
-					value: Box::new(
-						Node::BinaryOp { 
-							op: Operator::LogicalOr,
-							left: Box::new(
-								Node::BinaryOp { 
-									op: Operator::Equal, 
-									left: Box::new(Node::Int(2)), 
-									right: Box::new(Node::Int(2)) 
-								}
-							),
-							right: Box::new(
-								Node::BinaryOp { 
-									op: Operator::NotEqual, 
-									left: Box::new(Node::Float(3.5)), 
-									right: Box::new(Node::Float(3.6))
-								}
-							) 
-						}
-					)
+					name: String::from("counter"),
+					mutable: true,
+					declared_type: Some(Type::Int),
+					value: Box::new(Node::Int(0)),
+					span: Span::new(0, 0)
 				}),
-				next: Box::new(None)
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::VarAssignation {
+						name: String::from("counter"),
+						value: Box::new(Node::Int(1)),
+						span: Span::new(0, 26)
+					}),
+					next: Box::new(None)
+				}))
 			}
 		));
 	}
 
 	#[test]
-	fn if_statement_parsing(){
-		let mut lexer = Lexer::new("if condition == 2 { let test = 3; }");
+	fn compound_assignation_parsing(){
+		let mut lexer = Lexer::new("x -= 1; p.values[i] **= 2;");
 
 		let mut parser = Parser::new(&mut lexer);
 
@@ -431,35 +1965,48 @@ mod tests {
 
 		assert_eq!(ast,Some(
 			Node::InstructionList {
-				current: Box::new(Node::IfStatement { 
-					condition: Box::new(Node::BinaryOp { 
-						op: Operator::Equal, 
-						left: Box::new(
-							Node::Identifier(String::from("condition"))
-						), 
-						right: Box::new(
-							Node::Int(2)
-						) 
+				current: Box::new(Node::VarAssignation {
+					name: String::from("x"),
+					value: Box::new(Node::BinaryOp {
+						op: Operator::Minus,
+						left: Box::new(Node::Identifier { name: String::from("x"), span: Span::new(0, 0) }),
+						right: Box::new(Node::Int(1)),
+						span: Span::new(0, 2)
 					}),
-					body: Box::new(Some(Node::InstructionList { 
-						current: Box::new(
-							Node::VarDeclaration { 
-								name: String::from("test"),
-								declared_type: None,
-								value: Box::new(Node::Int(3))
-							}
-						), 
-						next: Box::new(None) 
-					})) 
+					span: Span::new(0, 0)
 				}),
-				next: Box::new(None)
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::ElementAssignation {
+						name: String::from("p"),
+						path: Vec::from([
+							Accessor::Field(String::from("values")),
+							Accessor::Index(Node::Identifier { name: String::from("i"), span: Span::new(0, 17) })
+						]),
+						value: Box::new(Node::BinaryOp {
+							op: Operator::Power,
+							left: Box::new(Node::Index {
+								array: Box::new(Node::FieldAccess {
+									value: Box::new(Node::Identifier { name: String::from("p"), span: Span::new(0, 8) }),
+									field: String::from("values"),
+									span: Span::new(0, 8)
+								}),
+								index: Box::new(Node::Identifier { name: String::from("i"), span: Span::new(0, 17) }),
+								span: Span::new(0, 8)
+							}),
+							right: Box::new(Node::Int(2)),
+							span: Span::new(0, 20)
+						}),
+						span: Span::new(0, 8)
+					}),
+					next: Box::new(None)
+				}))
 			}
 		));
 	}
 
 	#[test]
-	fn function_declaration_parsing(){
-		let mut lexer = Lexer::new("fn foo(arg1: int, arg2: int, arg3: int) -> int { return 2; let test = 2; }");
+	fn increment_parsing(){
+		let mut lexer = Lexer::new("x++; values[0]--;");
 
 		let mut parser = Parser::new(&mut lexer);
 
@@ -467,17 +2014,87 @@ mod tests {
 
 		assert_eq!(ast,Some(
 			Node::InstructionList {
-				current: Box::new(Node::FunctionDeclaration { 
-					name: String::from("foo"), 
-					param_names: Vec::from([String::from("arg1"), String::from("arg2"), String::from("arg3")]),
-					param_types: Vec::from([Type::Int, Type::Int, Type::Int]),
-					return_type: Type::Int,
-					body: Box::new(Some(Node::InstructionList { 
-						current: Box::new(Node::ReturnStatement { 
-							value: Box::new(Some(Node::Int(2))) 
+				current: Box::new(Node::VarAssignation {
+					name: String::from("x"),
+					value: Box::new(Node::BinaryOp {
+						op: Operator::Add,
+						left: Box::new(Node::Identifier { name: String::from("x"), span: Span::new(0, 0) }),
+						right: Box::new(Node::Int(1)),
+						span: Span::new(0, 1)
+					}),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::ElementAssignation {
+						name: String::from("values"),
+						path: Vec::from([Accessor::Index(Node::Int(0))]),
+						value: Box::new(Node::BinaryOp {
+							op: Operator::Minus,
+							left: Box::new(Node::Index {
+								array: Box::new(Node::Identifier { name: String::from("values"), span: Span::new(0, 5) }),
+								index: Box::new(Node::Int(0)),
+								span: Span::new(0, 5)
+							}),
+							right: Box::new(Node::Int(1)),
+							span: Span::new(0, 14)
 						}),
-						next: Box::new(None) 
-					}))
+						span: Span::new(0, 5)
+					}),
+					next: Box::new(None)
+				}))
+			}
+		));
+	}
+
+	#[test]
+	fn if_expression_parsing(){
+		let mut lexer = Lexer::new("let x = if a { 1 } else if b { y = 2; f(); y } else { 3 };");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("x"),
+					mutable: false,
+					declared_type: None,
+					value: Box::new(Node::IfExpression {
+						condition: Box::new(Node::Identifier { name: String::from("a"), span: Span::new(0, 11) }),
+						then_branch: Box::new(Node::Block {
+							body: Box::new(None),
+							value: Box::new(Node::Int(1))
+						}),
+						else_branch: Box::new(Node::IfExpression {
+							condition: Box::new(Node::Identifier { name: String::from("b"), span: Span::new(0, 27) }),
+							then_branch: Box::new(Node::Block {
+								body: Box::new(Some(Node::InstructionList {
+									current: Box::new(Node::VarAssignation {
+										name: String::from("y"),
+										value: Box::new(Node::Int(2)),
+										span: Span::new(0, 31)
+									}),
+									next: Box::new(Some(Node::InstructionList {
+										current: Box::new(Node::FunctionCall {
+											name: String::from("f"),
+											args: Vec::new(),
+											span: Span::new(0, 38)
+										}),
+										next: Box::new(None)
+									}))
+								})),
+								value: Box::new(Node::Identifier { name: String::from("y"), span: Span::new(0, 43) })
+							}),
+							else_branch: Box::new(Node::Block {
+								body: Box::new(None),
+								value: Box::new(Node::Int(3))
+							}),
+							span: Span::new(0, 24)
+						}),
+						span: Span::new(0, 8)
+					}),
+					span: Span::new(0, 0)
 				}),
 				next: Box::new(None)
 			}
@@ -485,8 +2102,8 @@ mod tests {
 	}
 
 	#[test]
-	fn function_call_parsing(){
-		let mut lexer = Lexer::new("foo(arg1, arg2 + 2, arg3);");
+	fn const_declaration_parsing(){
+		let mut lexer = Lexer::new("const MAX: int = 10;");
 
 		let mut parser = Parser::new(&mut lexer);
 
@@ -494,26 +2111,20 @@ mod tests {
 
 		assert_eq!(ast,Some(
 			Node::InstructionList {
-				current: Box::new(Node::FunctionCall { 
-					name: String::from("foo"),
-					args: Vec::from([
-						Node::Identifier(String::from("arg1")),
-						Node::BinaryOp { 
-							op: Operator::Add, 
-							left: Box::new(Node::Identifier(String::from("arg2"))), 
-							right: Box::new(Node::Int(2)) 
-						},
-						Node::Identifier(String::from("arg3")),
-					])
+				current: Box::new(Node::ConstDeclaration {
+					name: String::from("MAX"),
+					declared_type: Type::Int,
+					value: Box::new(Node::Int(10)),
+					span: Span::new(0, 0)
 				}),
 				next: Box::new(None)
 			}
 		));
 	}
 
-	#[test]	
-	fn math_exp_parsing(){
-		let mut lexer = Lexer::new("let math: int = -1 * 3 + 4 * 2;");
+	#[test]
+	fn result_parsing(){
+		let mut lexer = Lexer::new("let x: result<int, string> = ok(f()?.value);");
 
 		let mut parser = Parser::new(&mut lexer);
 
@@ -522,33 +2133,55 @@ mod tests {
 		assert_eq!(ast,Some(
 			Node::InstructionList {
 				current: Box::new(Node::VarDeclaration { 
-					name: String::from("math"),
-					declared_type: Some(Type::Int),
-					value: Box::new(
-						Node::BinaryOp { 
-							op: Operator::Add,
-							left: Box::new(
-								Node::BinaryOp { 
-									op: Operator::Product, 
-									left: Box::new(Node::UnaryOp {
-										op: Operator::Minus,
-										right: Box::new(Node::Int(1)) 
-									}), 
-									right: Box::new(Node::Int(3)) 
-								}
-							),
-							right: Box::new(
-								Node::BinaryOp { 
-									op: Operator::Product, 
-									left: Box::new(Node::Int(4)), 
-									right: Box::new(Node::Int(2))
-								}
-							) 
-						}
-					)
+					name: String::from("x"),
+					mutable: false,
+					declared_type: Some(Type::Result(Box::new(Type::Int), Box::new(Type::String))),
+					value: Box::new(Node::Ok(Box::new(Node::FieldAccess {
+						value: Box::new(Node::Try {
+							value: Box::new(Node::FunctionCall { name: String::from("f"), args: Vec::new(), span: Span::new(0, 32) }),
+							span: Span::new(0, 35)
+						}),
+						field: String::from("value"),
+						span: Span::new(0, 36)
+					}))),
+					span: Span::new(0, 0)
 				}),
 				next: Box::new(None)
 			}
 		));
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn map_parsing(){
+		let mut lexer = Lexer::new("let ages: map[string, int] = map { \"alice\": 30, \"bob\": 25, }; ages[\"carol\"] = 41;");
+
+		let mut parser = Parser::new(&mut lexer);
+
+		let ast = parser.ast();
+
+		assert_eq!(ast,Some(
+			Node::InstructionList {
+				current: Box::new(Node::VarDeclaration { 
+					name: String::from("ages"),
+					mutable: false,
+					declared_type: Some(Type::Map(Box::new(Type::String), Box::new(Type::Int))),
+					value: Box::new(Node::Map {
+						keys: Vec::from([Node::String(String::from("alice")), Node::String(String::from("bob"))]),
+						values: Vec::from([Node::Int(30), Node::Int(25)]),
+						span: Span::new(0, 29)
+					}),
+					span: Span::new(0, 0)
+				}),
+				next: Box::new(Some(Node::InstructionList {
+					current: Box::new(Node::ElementAssignation {
+						name: String::from("ages"),
+						path: Vec::from([Accessor::Index(Node::String(String::from("carol")))]),
+						value: Box::new(Node::Int(41)),
+						span: Span::new(0, 62)
+					}),
+					next: Box::new(None)
+				}))
+			}
+		));
+	}
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+use crate::span::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallFrame {
+	pub function: String,
+	pub call_site: Span
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+	pub message: String,
+	pub span: Option<Span>,
+	// Innermost call first
+	pub call_stack: Vec<CallFrame>
+}
+
+impl RuntimeError {
+	pub fn new(message: String, span: Option<Span>) -> RuntimeError {
+		RuntimeError {
+			message,
+			span,
+			call_stack: Vec::new()
+		}
+	}
+}
+
+impl fmt::Display for RuntimeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Runtime error: {}", self.message)?;
+
+		match (self.call_stack.first(), self.span) {
+			(Some(frame), Some(span)) => write!(f, "\n\tat {} ({})", frame.function, span)?,
+			(Some(frame), None) => write!(f, "\n\tat {}", frame.function)?,
+			(None, Some(span)) => write!(f, "\n\tat {}", span)?,
+			(None, None) => {}
+		}
+
+		for (i, frame) in self.call_stack.iter().enumerate() {
+			match self.call_stack.get(i + 1) {
+				Some(caller) => write!(f, "\n\tcalled from {} ({})", caller.function, frame.call_site)?,
+				None => write!(f, "\n\tcalled from {}", frame.call_site)?
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn display_call_stack() {
+		let mut error = RuntimeError::new(String::from("Division by zero"), Some(Span::new(2, 9)));
+		error.call_stack.push(CallFrame { function: String::from("inner"), call_site: Span::new(6, 8) });
+		error.call_stack.push(CallFrame { function: String::from("outer"), call_site: Span::new(9, 13) });
+
+		assert_eq!(error.to_string(), "Runtime error: Division by zero\n\tat inner (line 3)\n\tcalled from outer (line 7)\n\tcalled from line 10");
+	}
+
+	#[test]
+	fn display_top_level() {
+		let error = RuntimeError::new(String::from("Division by zero"), Some(Span::new(0, 9)));
+
+		assert_eq!(error.to_string(), "Runtime error: Division by zero\n\tat line 1");
+	}
+}
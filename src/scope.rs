@@ -1,19 +1,207 @@
 use std::collections::HashMap;
 
-use crate::expression_result::ExpressionResult;
-use crate::function::Function;
-
+// Declarations of a block, `T` holds the ones specific to a visitor
 #[derive(Clone)]
-pub struct Scope {
-	pub memory: HashMap<String, ExpressionResult>,
-	pub functions: HashMap<String, Function>
+pub struct Scope<V, F, T = ()> {
+	pub variables: HashMap<String, V>,
+	pub functions: HashMap<String, F>,
+	pub types: T
 }
 
-impl Scope {
-	pub fn new() -> Scope {
-		Scope { 
-			memory: HashMap::new(),
+impl<V, F, T: Default> Scope<V, F, T> {
+	pub fn new() -> Scope<V, F, T> {
+		Scope {
+			variables: HashMap::new(),
 			functions: HashMap::new(), // TODO: Allow nested function, may not keep it
+			types: T::default()
+		}
+	}
+
+	pub fn with_variables(variables: impl IntoIterator<Item = (String, V)>) -> Scope<V, F, T> {
+		let mut scope = Scope::new();
+		scope.variables.extend(variables);
+
+		scope
+	}
+}
+
+impl<V, F, T: Default> Default for Scope<V, F, T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+// Scoping rules shared by the interpretor, the type checker and the code generator:
+// - The bodies of `if`, `if let` and match arms are blocks, their variables are dropped at their end
+// - A variable of a block shadows the ones of the same name outside of it, redeclaring it in the same block is a type error
+// - A function body starts a frame, the variables of the enclosing code are not visible from it
+// - Functions and constants are visible from every scope
+#[derive(Clone)]
+pub struct Scopes<V, F, T = ()> {
+	scopes: Vec<Scope<V, F, T>>,
+	// Declared at top level, seen behind the variables of any frame
+	constants: HashMap<String, V>,
+	// First scope of the current function, variables below it belong to the enclosing code
+	frame_start: usize
+}
+
+impl<V, F, T: Default> Scopes<V, F, T> {
+	pub fn new() -> Scopes<V, F, T> {
+		Scopes {
+			scopes: Vec::from([ Scope::new() ]),
+			constants: HashMap::new(),
+			frame_start: 0
 		}
 	}
-}
\ No newline at end of file
+
+	pub fn push_block(&mut self, scope: Scope<V, F, T>) {
+		self.scopes.push(scope);
+	}
+
+	pub fn pop_block(&mut self) -> Option<Scope<V, F, T>> {
+		self.scopes.pop()
+	}
+
+	// Returns the frame to give back to `pop_frame`
+	pub fn push_frame(&mut self, scope: Scope<V, F, T>) -> usize {
+		let caller_frame_start = self.frame_start;
+		self.scopes.push(scope);
+		self.frame_start = self.scopes.len() - 1;
+
+		caller_frame_start
+	}
+
+	// Drops the blocks left open by an early return too, they are returned outermost first
+	pub fn pop_frame(&mut self, caller_frame_start: usize) -> Vec<Scope<V, F, T>> {
+		let frame = self.scopes.split_off(self.frame_start);
+		self.frame_start = caller_frame_start;
+
+		frame
+	}
+
+	pub fn in_function(&self) -> bool {
+		self.frame_start > 0
+	}
+
+	// Outside of any function or block
+	pub fn at_top_level(&self) -> bool {
+		self.scopes.len() == 1
+	}
+
+	pub fn current_mut(&mut self) -> &mut Scope<V, F, T> {
+		self.scopes.last_mut().expect("No scope")
+	}
+
+	// Innermost scope last
+	pub fn iter(&self) -> std::slice::Iter<'_, Scope<V, F, T>> {
+		self.scopes.iter()
+	}
+
+	// Whether `name` is declared in the current block, shadowing it is only allowed from a nested one
+	pub fn is_declared_in_block(&self, name: &str) -> bool {
+		self.scopes.last().is_some_and(|scope| scope.variables.contains_key(name))
+	}
+
+	pub fn insert_var(&mut self, name: &str, value: V) {
+		self.current_mut().variables.insert(String::from(name), value);
+	}
+
+	pub fn resolve_var(&self, name: &str) -> Option<&V> {
+		self.scopes[self.frame_start..].iter().rev().find_map(|scope| scope.variables.get(name))
+			.or_else(|| self.constants.get(name))
+	}
+
+	pub fn resolve_var_mut(&mut self, name: &str) -> Option<&mut V> {
+		self.scopes[self.frame_start..].iter_mut().rev().find_map(|scope| scope.variables.get_mut(name))
+			.or_else(|| self.constants.get_mut(name))
+	}
+
+	pub fn insert_constant(&mut self, name: &str, value: V) {
+		self.constants.insert(String::from(name), value);
+	}
+
+	pub fn is_constant(&self, name: &str) -> bool {
+		self.constants.contains_key(name)
+	}
+
+	pub fn insert_function(&mut self, name: &str, function: F) {
+		self.current_mut().functions.insert(String::from(name), function);
+	}
+
+	// Whether `name` is declared in the current function
+	pub fn is_function_in_frame(&self, name: &str) -> bool {
+		self.scopes[self.frame_start..].iter().any(|scope| scope.functions.contains_key(name))
+	}
+
+	pub fn resolve_function(&self, name: &str) -> Option<&F> {
+		self.scopes.iter().rev().find_map(|scope| scope.functions.get(name))
+	}
+}
+
+impl<V, F, T: Default> Default for Scopes<V, F, T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn block_shadowing() {
+		let mut scopes: Scopes<i64, ()> = Scopes::new();
+		scopes.insert_var("x", 1);
+
+		scopes.push_block(Scope::new());
+		assert!(!scopes.is_declared_in_block("x"));
+		scopes.insert_var("x", 2);
+		scopes.insert_var("y", 3);
+		assert_eq!(scopes.resolve_var("x"), Some(&2));
+		assert!(scopes.is_declared_in_block("y"));
+
+		scopes.pop_block();
+		assert_eq!(scopes.resolve_var("x"), Some(&1));
+		assert_eq!(scopes.resolve_var("y"), None);
+	}
+
+	#[test]
+	fn frame_hides_enclosing_variables() {
+		let mut scopes: Scopes<i64, &str> = Scopes::new();
+		scopes.insert_var("x", 1);
+		scopes.insert_function("f", "body");
+
+		let caller_frame_start = scopes.push_frame(Scope::with_variables([(String::from("param"), 2)]));
+		scopes.push_block(Scope::new());
+		assert!(scopes.in_function());
+		assert_eq!(scopes.resolve_var("x"), None);
+		assert_eq!(scopes.resolve_var("param"), Some(&2));
+		assert_eq!(scopes.resolve_function("f"), Some(&"body"));
+		assert!(!scopes.is_function_in_frame("f"));
+
+		// The block is still open, like after a return from inside an `if`
+		let frame = scopes.pop_frame(caller_frame_start);
+		assert_eq!(frame.len(), 2);
+		assert!(!scopes.in_function());
+		assert_eq!(scopes.resolve_var("x"), Some(&1));
+		assert_eq!(scopes.resolve_var("param"), None);
+	}
+
+	#[test]
+	fn constants_visible_from_frames() {
+		let mut scopes: Scopes<i64, ()> = Scopes::new();
+		assert!(scopes.at_top_level());
+		scopes.insert_constant("MAX", 100);
+
+		let caller_frame_start = scopes.push_frame(Scope::new());
+		assert!(!scopes.at_top_level());
+		assert_eq!(scopes.resolve_var("MAX"), Some(&100));
+
+		// Shadowed by a variable of the function
+		scopes.insert_var("MAX", 1);
+		assert_eq!(scopes.resolve_var("MAX"), Some(&1));
+
+		scopes.pop_frame(caller_frame_start);
+		assert!(scopes.is_constant("MAX"));
+	}
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+	pub line: usize,
+	pub col: usize
+}
+
+impl Span {
+	pub fn new(line: usize, col: usize) -> Span {
+		Span {
+			line,
+			col
+		}
+	}
+}
+
+impl fmt::Display for Span {
+	// Lines and columns are stored 0-based, but displayed as in editors
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "line {}", self.line + 1)
+	}
+}
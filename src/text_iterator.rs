@@ -8,13 +8,14 @@ pub struct Symbol {
 impl Symbol {
 	fn new(value: char, col: usize, line: usize) -> Symbol {
 		Symbol{
-			value: value,
-			col: col,
-			line: line
+			value,
+			col,
+			line
 		}
 	}
 }
 
+#[derive(Clone)]
 pub struct TextIterator<'a> {
 	raw_iterator : std::str::Chars<'a>,
 	
@@ -23,7 +24,7 @@ pub struct TextIterator<'a> {
 }
 
 impl TextIterator<'_> {
-	pub fn new(text: &str) -> TextIterator {
+	pub fn new(text: &str) -> TextIterator<'_> {
 		TextIterator {
 			raw_iterator: text.chars(),
 			current_col: 0,
@@ -1,7 +1,20 @@
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Type {
 	Void,
 	Int,
 	Float,
-	Bool
+	Bool,
+	String,
+	Array(Box<Type>),
+	Map(Box<Type>, Box<Type>),
+	Option(Box<Type>),
+	Result(Box<Type>, Box<Type>),
+	// Resolved by name against the declared structs and enums
+	Named(String),
+	// Omitted annotation, inferred by the type checker
+	Infer,
+	// Unknown type of the type checker, bound by unification
+	Var(usize),
+	// Type of an ill-typed expression, matches any type so that the error is reported once
+	Error
 }
\ No newline at end of file
@@ -1,272 +1,1948 @@
-use core::panic;
 use std::collections::HashMap;
+use std::fmt;
 
-use crate::node::Node;
+use crate::node::{Node, Accessor, Pattern, TypeParam};
 use crate::operator::Operator;
 use crate::visitor::{Visitor, Visitable};
 use crate::r#type::Type;
+use crate::span::Span;
+use crate::scope::{Scope, Scopes};
+use crate::type_error::TypeError;
+use crate::type_warning::TypeWarning;
+use crate::builtin;
+use crate::optimizer::OptimizerVisitor;
 
 #[derive(Clone, Debug)]
 struct FunctionType {
+	// Names of the type parameters, with their bounds
+	type_params: Vec<(String, Vec<Bound>)>,
 	return_type: Type,
 	param_types: Vec<Type>
 }
 
-// TODO: Duplication
-#[derive(Clone)]
-struct TypeScope {
-	pub variables: HashMap<String, Type>,
-	pub functions: HashMap<String, FunctionType>,
+// Built-in bounds of type parameters, `Ord` implies `Eq`
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Bound {
+	Eq,
+	Ord,
+	Num
 }
 
-impl TypeScope {
-	fn new() -> TypeScope {
-		TypeScope { 
-			variables: HashMap::new(),
-			functions: HashMap::new() // TODO: Allow nested function, may not keep it
+impl Bound {
+	const ALL: [Bound; 3] = [Bound::Eq, Bound::Ord, Bound::Num];
+
+	fn from_name(name: &str) -> Option<Bound> {
+		Bound::ALL.into_iter().find(|bound| bound.to_string() == name)
+	}
+
+	// Whether a type parameter declared with this bound can be used where `required` is
+	fn implies(self, required: Bound) -> bool {
+		self == required || (self == Bound::Ord && required == Bound::Eq)
+	}
+}
+
+impl fmt::Display for Bound {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Bound::Eq => write!(f, "Eq"),
+			Bound::Ord => write!(f, "Ord"),
+			Bound::Num => write!(f, "Num")
+		}
+	}
+}
+
+// Types that can be hashed, floats are left out
+fn is_map_key(key_type: &Type) -> bool {
+	matches!(key_type, Type::Int | Type::Bool | Type::String | Type::Error)
+}
+
+#[derive(Clone, Debug)]
+struct StructType {
+	field_names: Vec<String>,
+	field_types: Vec<Type>
+}
+
+#[derive(Clone, Debug)]
+struct EnumType {
+	variant_names: Vec<String>,
+	variant_types: Vec<Vec<Type>>
+}
+
+// Matches anything, fills the columns of the patterns without sub-patterns in `is_exhaustive`
+static WILDCARD: Pattern = Pattern::Wildcard;
+
+#[derive(Clone, Default)]
+struct TypeDeclarations {
+	structs: HashMap<String, StructType>,
+	enums: HashMap<String, EnumType>,
+	// Type parameters of the checked function, with their bounds
+	type_params: HashMap<String, Vec<Bound>>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum VariableKind {
+	Variable,
+	Parameter,
+	Binding,
+	Constant
+}
+
+// Named in the diagnostics
+impl fmt::Display for VariableKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			VariableKind::Variable => write!(f, "variable"),
+			VariableKind::Parameter => write!(f, "parameter"),
+			VariableKind::Binding => write!(f, "binding"),
+			VariableKind::Constant => write!(f, "constant")
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Variable {
+	var_type: Type,
+	// Read at least once, assigning it doesn't count
+	used: bool,
+	// Only `let mut` variables can be assigned
+	mutable: bool,
+	kind: VariableKind,
+	// Declaration, parameters and bindings don't have one
+	span: Option<Span>
+}
+
+impl Variable {
+	fn new(var_type: Type, mutable: bool, kind: VariableKind, span: Option<Span>) -> Variable {
+		Variable {
+			var_type,
+			used: false,
+			mutable,
+			kind,
+			span
 		}
 	}
 }
 
+type TypeScope = Scope<Variable, FunctionType, TypeDeclarations>;
+
+// Rebuild `value_type` with `transform` applied to its element types
+fn map_elements(value_type: &Type, transform: &mut impl FnMut(&Type) -> Type) -> Type {
+	match value_type {
+		Type::Array(element_type) => Type::Array(Box::new(transform(element_type))),
+		Type::Option(value_type) => Type::Option(Box::new(transform(value_type))),
+		Type::Map(key_type, value_type) => Type::Map(Box::new(transform(key_type)), Box::new(transform(value_type))),
+		Type::Result(value_type, error_type) => Type::Result(Box::new(transform(value_type)), Box::new(transform(error_type))),
+		other => other.clone()
+	}
+}
+
 pub struct TypeCheckerVisitor {
 	result: Type,
-	scopes: Vec<TypeScope>
+	scopes: Scopes<Variable, FunctionType, TypeDeclarations>,
+	// Declared return type of the checked function
+	return_type: Option<Type>,
+	// Whether the checked function has a return statement
+	has_return: bool,
+	// Number of enclosing `if` expressions, a return can't leave their blocks
+	value_blocks: usize,
+	// Type bound to each type variable by unification
+	substitution: Vec<Option<Type>>,
+	// Bounds required by the uses of each unbound type variable, with the location of the first use
+	var_bounds: Vec<Vec<(Bound, Span)>>,
+	// Map key types not inferred yet where they were used, checked at the end of the function or the program
+	map_keys: Vec<(Type, Span)>,
+	// Values of the constants declared so far, to compute the next ones
+	constant_values: HashMap<String, Node>,
+	errors: Vec<TypeError>,
+	warnings: Vec<TypeWarning>
+}
+
+impl Default for TypeCheckerVisitor {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl TypeCheckerVisitor {
 	pub fn new() -> TypeCheckerVisitor {
 		TypeCheckerVisitor { 
 			result: Type::Void,
-			scopes: Vec::from([ TypeScope::new() ]),
+			scopes: Scopes::new(),
+			return_type: None,
+			has_return: false,
+			value_blocks: 0,
+			substitution: Vec::new(),
+			var_bounds: Vec::new(),
+			map_keys: Vec::new(),
+			constant_values: HashMap::new(),
+			errors: Vec::new(),
+			warnings: Vec::new()
 		}
 	}
 
-	pub fn check(&mut self, ast: &Node) {
+	// Checks the whole program, every type error found is returned
+	pub fn check(&mut self, ast: &Node) -> Result<(), Vec<TypeError>> {
 		ast.accept(self);
+		self.check_map_keys(0);
 
-		for scope in self.scopes.iter() {
-			println!("{:?}", scope.functions);
-			println!("{:?}", scope.variables);
+		if self.errors.is_empty() {
+			Ok(())
+		}
+		else {
+			Err(std::mem::take(&mut self.errors))
 		}
 	}
 
-	// TODO: Code duplication
-	fn insert_var(&mut self, name: &String, value: Type) {
-		let current = self.scopes.last_mut();
+	// Warnings of the last check, given even when it failed
+	pub fn warnings(&self) -> &[TypeWarning] {
+		&self.warnings
+	}
+
+	// The ill-typed expression gets `Type::Error`, so that its uses are not reported again
+	fn error(&mut self, message: String, span: Option<Span>) -> Type {
+		self.errors.push(TypeError::new(message, span));
+
+		Type::Error
+	}
 
-		if let Some(scope) = current {
-			scope.variables.insert(String::from(name), value);
+	// Shadowing a variable is only allowed from a nested block
+	fn declare_var(&mut self, name: &str, var: Variable) {
+		if self.scopes.is_declared_in_block(name) || (self.scopes.at_top_level() && self.scopes.is_constant(name)) {
+			self.error(format!("{} is already declared in this scope", name), var.span);
 		}
-		else {
-			panic!("No scope");
+
+		self.scopes.insert_var(name, var);
+	}
+
+	// Type of the assigned variable, the error points at its declaration when it is immutable
+	fn assigned_var_type(&mut self, name: &str, span: &Span) -> Type {
+		let Some(var) = self.scopes.resolve_var(name).cloned() else {
+			return self.error(format!("Not declared identifier {}", name), Some(*span));
+		};
+
+		if !var.mutable {
+			let declaration = match var.span {
+				Some(declaration_span) => format!(" declared at {}", declaration_span),
+				None => String::new()
+			};
+			let hint = match var.kind {
+				VariableKind::Variable => format!(", declare it with `let mut {}` to allow it", name),
+				_ => String::from(", copy it in a `let mut` variable to modify it")
+			};
+			self.error(format!("Can't assign to immutable {} {}{}{}", var.kind, name, declaration, hint), Some(*span));
 		}
+
+		var.var_type
 	}
 
-	fn insert_function(&mut self, name: &str, return_type: Type, param_types: Vec<Type>) {
-		let current = self.scopes.last_mut();
+	fn pop_block(&mut self) {
+		if let Some(scope) = self.scopes.pop_block() {
+			self.warn_unused(scope);
+		}
+	}
 
-		if let Some(scope) = current {
-			scope.functions.insert(String::from(name), FunctionType { return_type: return_type, param_types: param_types });
+	fn pop_frame(&mut self, caller_frame_start: usize) {
+		for scope in self.scopes.pop_frame(caller_frame_start) {
+			self.warn_unused(scope);
 		}
-		else {
-			panic!("No scope");
+	}
+
+	// The variables of the program scope are never dropped, they aren't reported
+	// Like `_`, a leading underscore marks a variable as intentionally unused
+	fn warn_unused(&mut self, scope: TypeScope) {
+		let mut unused: Vec<(String, Variable)> = scope.variables.into_iter()
+			.filter(|(name, var)| !var.used && !name.starts_with('_'))
+			.collect();
+		unused.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+		for (name, var) in unused {
+			self.warnings.push(TypeWarning::new(format!("Unused {} {}", var.kind, name), var.span));
 		}
 	}
 
-	fn resolve_scope_function(&self, name: &str) -> Option<&FunctionType> {
-		for scope in self.scopes.iter().rev() {
-			let result = scope.functions.get(name);
+	// Structs are visible from everywhere, like functions
+	fn resolve_scope_struct(&self, name: &str) -> Option<&StructType> {
+		self.scopes.iter().rev().find_map(|scope| scope.types.structs.get(name))
+	}
+
+	fn resolve_scope_enum(&self, name: &str) -> Option<&EnumType> {
+		self.scopes.iter().rev().find_map(|scope| scope.types.enums.get(name))
+	}
+
+	// Bounds of `value_type` when it is a type parameter of the checked function
+	fn resolve_type_param(&self, value_type: &Type) -> Option<&Vec<Bound>> {
+		let Type::Named(name) = value_type else {
+			return None;
+		};
 
-			if let Some(_) = result {
-				return result;
+		self.scopes.iter().rev().find_map(|scope| scope.types.type_params.get(name))
+	}
+
+	fn fresh_var(&mut self) -> Type {
+		self.substitution.push(None);
+		self.var_bounds.push(Vec::new());
+
+		Type::Var(self.substitution.len() - 1)
+	}
+
+	// Follow the bound variables at the top of `value_type`
+	fn shallow_resolve(&self, value_type: &Type) -> Type {
+		let mut current = value_type.clone();
+		while let Type::Var(var) = current {
+			match &self.substitution[var] {
+				Some(bound) => current = bound.clone(),
+				None => break
 			}
 		}
 
-		None
+		current
 	}
 
-	fn resolve_scope_var(&self, name: &str) -> Option<&Type> {
-		for scope in self.scopes.iter().rev() {
-			let result = scope.variables.get(name);
+	// `value_type` with all its bound variables replaced
+	fn resolve(&self, value_type: &Type) -> Type {
+		map_elements(&self.shallow_resolve(value_type), &mut |element_type| self.resolve(element_type))
+	}
 
-			if let Some(_) = result {
-				return result;
+	// Unbound variables of `value_type`, in order of appearance
+	fn free_vars(&self, value_type: &Type, vars: &mut Vec<usize>) {
+		match self.shallow_resolve(value_type) {
+			Type::Var(var) if !vars.contains(&var) => vars.push(var),
+			Type::Array(element_type) | Type::Option(element_type) => self.free_vars(&element_type, vars),
+			Type::Map(first, second) | Type::Result(first, second) => {
+				self.free_vars(&first, vars);
+				self.free_vars(&second, vars);
+			},
+			_ => {}
+		}
+	}
+
+	// Bind the type variables to make `expected` and `actual` the same type, false when they can't be
+	fn unify(&mut self, expected: &Type, actual: &Type) -> bool {
+		let expected = self.shallow_resolve(expected);
+		let actual = self.shallow_resolve(actual);
+
+		match (&expected, &actual) {
+			(Type::Error, _) | (_, Type::Error) => true,
+			(Type::Var(expected_var), Type::Var(actual_var)) if expected_var == actual_var => true,
+			(Type::Var(var), other) | (other, Type::Var(var)) => self.bind(*var, other),
+			(Type::Array(expected), Type::Array(actual)) | (Type::Option(expected), Type::Option(actual)) => self.unify(expected, actual),
+			(Type::Map(expected_key, expected_value), Type::Map(actual_key, actual_value)) |
+			(Type::Result(expected_key, expected_value), Type::Result(actual_key, actual_value)) => {
+				self.unify(expected_key, actual_key) && self.unify(expected_value, actual_value)
+			},
+			_ => expected == actual
+		}
+	}
+
+	fn bind(&mut self, var: usize, value_type: &Type) -> bool {
+		// A type can't contain itself
+		let mut vars = Vec::new();
+		self.free_vars(value_type, &mut vars);
+		if vars.contains(&var) {
+			return false;
+		}
+
+		let bounds = std::mem::take(&mut self.var_bounds[var]);
+		self.substitution[var] = Some(value_type.clone());
+
+		for (bound, span) in bounds.iter() {
+			if !self.require_bound(value_type, *bound, span) {
+				let message = format!("{:?} is not {}", self.resolve(value_type), bound);
+				self.error(message, Some(*span));
 			}
 		}
 
-		None
+		true
 	}
 
-	fn apply_op(op: &Operator, original_type: Type) -> Type {
-		match op {
-			Operator::Equal => Type::Bool,
-			Operator::Greater => Type::Bool,
-			Operator::GreaterOrEq => Type::Bool,
-			Operator::LogicalAnd => Type::Bool,
-			Operator::LogicalOr => Type::Bool,
-			Operator::Lower => Type::Bool,
-			Operator::LowerOrEq => Type::Bool,
-			Operator::NotEqual => Type::Bool,
-			Operator::Not => Type::Bool,
-			_ => original_type
+	// A type not inferred yet keeps the bound until it is
+	fn require_bound(&mut self, value_type: &Type, bound: Bound, span: &Span) -> bool {
+		match self.shallow_resolve(value_type) {
+			Type::Var(var) => {
+				if !self.var_bounds[var].iter().any(|(required, _)| *required == bound) {
+					self.var_bounds[var].push((bound, *span));
+				}
+
+				true
+			},
+			resolved => self.satisfies(&resolved, bound)
 		}
 	}
-}
 
-impl Visitor for TypeCheckerVisitor {
-	fn visit_int(&mut self, _: i64) {
-		self.result = Type::Int;
+	fn satisfies(&self, value_type: &Type, bound: Bound) -> bool {
+		if *value_type == Type::Error {
+			return true;
+		}
+
+		if let Some(bounds) = self.resolve_type_param(value_type) {
+			return bounds.iter().any(|declared| declared.implies(bound));
+		}
+
+		match bound {
+			Bound::Eq => matches!(value_type, Type::Int | Type::Float | Type::Bool | Type::String),
+			Bound::Ord => matches!(value_type, Type::Int | Type::Float | Type::String),
+			Bound::Num => matches!(value_type, Type::Int | Type::Float)
+		}
 	}
 
-	fn visit_float(&mut self, _: f64) {
-		self.result = Type::Float;
+	// Bound a type parameter needs to be an operand of `op`
+	fn required_bound(op: &Operator) -> Option<Bound> {
+		match op {
+			Operator::Equal | Operator::NotEqual => Some(Bound::Eq),
+			Operator::Lower | Operator::LowerOrEq | Operator::Greater | Operator::GreaterOrEq => Some(Bound::Ord),
+			Operator::Add | Operator::Minus | Operator::Product | Operator::Divide | Operator::Modulo | Operator::Power => Some(Bound::Num),
+			_ => None
+		}
 	}
 
-	fn visit_identifier(&mut self, name: &String) {
-		match self.resolve_scope_var(name) {
-			Some(var) => {
-				self.result = *var;
+	// A key type not inferred yet is checked once it is, by `check_map_keys`
+	fn check_map_key(&mut self, key_type: &Type, span: &Span) {
+		match self.resolve(key_type) {
+			Type::Var(_) => self.map_keys.push((key_type.clone(), *span)),
+			resolved if !is_map_key(&resolved) => {
+				self.error(format!("{:?} can't be a map key", resolved), Some(*span));
 			},
-			None => panic!("Not declared identifier {}", name)
+			_ => {}
 		}
 	}
 
-	fn visit_bool(&mut self, _: bool) {
-		self.result = Type::Bool;
+	// Check the key types left by `check_map_key` since the `first` one
+	fn check_map_keys(&mut self, first: usize) {
+		for (key_type, span) in self.map_keys.split_off(first) {
+			match self.resolve(&key_type) {
+				// Left to the callers, it would be a type parameter and they can't be map keys
+				Type::Var(_) if self.scopes.in_function() => {
+					self.error(String::from("Can't infer the type of the map keys, annotate it"), Some(span));
+				},
+				Type::Var(_) => {},
+				resolved if !is_map_key(&resolved) => {
+					self.error(format!("{:?} can't be a map key", resolved), Some(span));
+				},
+				_ => {}
+			}
+		}
 	}
 
-	fn visit_binary_op(&mut self, op: &Operator, left: &Node, right: &Node) {
-		left.accept(self);
-		let rhs_type = self.result;
+	fn apply_generic_op(&mut self, op: &Operator, param_type: Type, span: &Span) -> Type {
+		let Some(bound) = TypeCheckerVisitor::required_bound(op) else {
+			return self.error(format!("{:?} can't be applied on the type parameter {:?}", op, param_type), Some(*span));
+		};
 
-		right.accept(self);
+		if !self.satisfies(&param_type, bound) {
+			return self.error(format!("{:?} requires {:?} to be bounded by {}", op, param_type, bound), Some(*span));
+		}
+
+		// Comparisons give a bool, arithmetic keeps the operand type
+		if bound == Bound::Num {
+			param_type
+		}
+		else {
+			Type::Bool
+		}
+	}
 
-		if self.result != rhs_type {
-			// TODO: Better error display
-			panic!("Wrong type operand");
+	// The operand type is not inferred yet, the operator bounds it or gives it its type
+	fn apply_unknown_op(&mut self, op: &Operator, operand_type: Type, span: &Span) -> Type {
+		if let Some(bound) = TypeCheckerVisitor::required_bound(op) {
+			self.require_bound(&operand_type, bound, span);
+
+			return if bound == Bound::Num { operand_type } else { Type::Bool };
 		}
 
-		self.result = TypeCheckerVisitor::apply_op(op, rhs_type);
+		let known_type = if matches!(op, Operator::LogicalAnd | Operator::LogicalOr | Operator::Not) { Type::Bool } else { Type::Int };
+		self.unify(&operand_type, &known_type);
+
+		self.apply_op(op, known_type, span)
 	}
 
-	fn visit_unary_op(&mut self, op: &Operator, right: &Node) {
-		right.accept(self);
+	// Each call of a generic function gets its own types for the type parameters
+	fn instantiate(generic: &Type, instances: &HashMap<String, Type>) -> Type {
+		match generic {
+			Type::Named(name) if instances.contains_key(name) => instances[name].clone(),
+			_ => map_elements(generic, &mut |element_type| TypeCheckerVisitor::instantiate(element_type, instances))
+		}
+	}
+
+	fn replace_vars(&self, value_type: &Type, names: &HashMap<usize, Type>) -> Type {
+		match self.shallow_resolve(value_type) {
+			Type::Var(var) => names.get(&var).cloned().unwrap_or(Type::Var(var)),
+			resolved => map_elements(&resolved, &mut |element_type| self.replace_vars(element_type, names))
+		}
+	}
+
+	// Parameter types left unknown by the body become type parameters of the function
+	fn generalize(&mut self, name: &str, span: &Span, type_params: &[(String, Vec<Bound>)], param_types: &[Type], return_type: &Type) -> FunctionType {
+		let mut vars = Vec::new();
+		for param_type in param_types.iter() {
+			self.free_vars(param_type, &mut vars);
+		}
+
+		let mut return_vars = Vec::new();
+		self.free_vars(return_type, &mut return_vars);
+		return_vars.retain(|var| !vars.contains(var));
+		if !return_vars.is_empty() {
+			self.error(format!("Can't infer the return type of {}, annotate it", name), Some(*span));
+		}
+		for var in return_vars {
+			self.substitution[var] = Some(Type::Error);
+		}
+
+		// Named with a quote, they can't clash with the type parameters of the program
+		let mut type_params = type_params.to_vec();
+		let mut names = HashMap::new();
+		for (i, var) in vars.iter().enumerate() {
+			let param_name = format!("'{}", i);
 
-		if *op == Operator::Not && self.result != Type::Bool {
-			// TODO: Better error display
-			panic!("Can't use Operator::Not on other type than Type::Bool");
+			type_params.push((param_name.clone(), self.var_bounds[*var].iter().map(|(bound, _)| *bound).collect()));
+			names.insert(*var, Type::Named(param_name));
 		}
-		else if *op != Operator::Not && self.result == Type::Bool {
-			// TODO: Better error display
-			panic!("Type::Bool only support Operator::Not as unary op");
+
+		FunctionType {
+			type_params,
+			return_type: self.replace_vars(return_type, &names),
+			param_types: param_types.iter().map(|param_type| self.replace_vars(param_type, &names)).collect()
 		}
 	}
 
-	fn visit_var_assignation(&mut self, name: &String, value: &Node) {
-		value.accept(self);
-		let result = &self.result;
 
-		match self.resolve_scope_var(name) {
-			Some(var) => {
-				if var != result {
-					// TODO: Better error display
-					panic!("Wrong type assignation");
-				}
-			},
-			None => panic!("Not declared identifier {}", name)
+	fn variant_types(&mut self, enum_name: &str, variant: &str, span: Option<Span>) -> Option<Vec<Type>> {
+		let Some(enum_def) = self.resolve_scope_enum(enum_name) else {
+			self.error(format!("Undefined enum {}", enum_name), span);
+			return None;
+		};
+
+		match enum_def.variant_names.iter().position(|variant_name| variant_name == variant) {
+			Some(position) => Some(enum_def.variant_types[position].clone()),
+			None => {
+				self.error(format!("{} has no variant {}", enum_name, variant), span);
+				None
+			}
 		}
 	}
 
-	fn visit_var_declaration(&mut self, name: &String, declared_type: &Option<Type>, value: &Node) {
-		value.accept(self);
+	fn check_type_name(&mut self, name: &str, span: &Span) {
+		let declared = self.scopes.iter().last().is_some_and(|scope| scope.types.structs.contains_key(name) || scope.types.enums.contains_key(name));
+		if declared {
+			self.error(format!("Type {} already declared", name), Some(*span));
+		}
+	}
 
-		if let Some(explicit_type) = declared_type {
-			if *explicit_type != self.result {
-				// TODO: Better error display
-				panic!("Declared type doesn't match expression");
+	// False when `value_type` must be unwrapped before use
+	fn check_unwrapped(&mut self, value_type: &Type, span: &Span) -> bool {
+		let message = match value_type {
+			Type::Option(_) => format!("{:?} can't be used as a plain value, unwrap it with match or if let", value_type),
+			Type::Result(_, _) => format!("{:?} can't be used as a plain value, unwrap it with match, if let or ?", value_type),
+			_ => return true
+		};
+
+		self.error(message, Some(*span));
+		false
+	}
+
+	fn check_bindings(&mut self, pattern: &Pattern, span: Option<Span>) {
+		let bindings = pattern.bindings();
+		for (i, binding) in bindings.iter().enumerate() {
+			if bindings[..i].contains(binding) {
+				self.error(format!("{} bound twice in the same pattern", binding), span);
 			}
 		}
+	}
+
+	// Type of `container[index]`
+	fn element_type(&mut self, container_type: Type, index: &Node, span: &Span) -> Type {
+		index.accept(self);
+		let index_type = self.result.clone();
+
+		let container_type = self.resolve(&container_type);
+		if !self.check_unwrapped(&container_type, span) {
+			return Type::Error;
+		}
+
+		match container_type {
+			Type::Array(element_type) => {
+				if !self.unify(&Type::Int, &index_type) {
+					let message = format!("Array index must be Type::Int, got {:?}", self.resolve(&index_type));
+					self.error(message, Some(*span));
+				}
+
+				*element_type
+			},
+			Type::Map(key_type, value_type) => {
+				if !self.unify(&key_type, &index_type) {
+					let message = format!("Map key must be {:?}, got {:?}", self.resolve(&key_type), self.resolve(&index_type));
+					self.error(message, Some(*span));
+				}
 
-		self.insert_var(name, self.result);
+				*value_type
+			},
+			Type::Error => Type::Error,
+			Type::Var(_) => self.error(String::from("Can't infer the type of the indexed value, annotate it"), Some(*span)),
+			other => self.error(format!("{:?} can't be indexed", other), Some(*span))
+		}
 	}
 
-	fn visit_return_statement(&mut self, value: &Option<Node>) {
-		if let Some(exp) = value {
-			exp.accept(self);
+	// `checked_type` with its undefined names replaced by `Type::Error`
+	fn check_type(&mut self, checked_type: &Type, span: &Span) -> Type {
+		match checked_type {
+			Type::Map(key_type, value_type) => {
+				let key_type = self.check_type(key_type, span);
+				if !is_map_key(&key_type) {
+					self.error(format!("{:?} can't be a map key", key_type), Some(*span));
+				}
+
+				Type::Map(Box::new(key_type), Box::new(self.check_type(value_type, span)))
+			},
+			Type::Named(name) if self.resolve_scope_struct(name).is_none() && self.resolve_scope_enum(name).is_none() && self.resolve_type_param(checked_type).is_none() => {
+				self.error(format!("Undefined type {}", name), Some(*span))
+			},
+			_ => map_elements(checked_type, &mut |element_type| self.check_type(element_type, span))
 		}
-		else {
-			self.result = Type::Void;
+	}
+
+	fn field_type(&mut self, struct_type: &Type, field: &str, span: &Span) -> Type {
+		let struct_type = self.resolve(struct_type);
+		if !self.check_unwrapped(&struct_type, span) {
+			return Type::Error;
+		}
+
+		let name = match struct_type {
+			Type::Named(name) => name,
+			Type::Error => return Type::Error,
+			Type::Var(_) => return self.error(format!("Can't infer the type of the value with the field {}, annotate it", field), Some(*span)),
+			_ => return self.error(format!("{:?} has no field {}", struct_type, field), Some(*span))
+		};
+
+		let field_type = self.resolve_scope_struct(&name).and_then(|struct_def| {
+			struct_def.field_names.iter().position(|field_name| field_name == field).map(|position| struct_def.field_types[position].clone())
+		});
+
+		match field_type {
+			Some(field_type) => field_type,
+			None => self.error(format!("{} has no field {}", name, field), Some(*span))
 		}
 	}
 
-	fn visit_if_statement(&mut self, condition: &Node, body: &Option<Node>) {
-		condition.accept(self);
-		if self.result != Type::Bool {
-			// TODO: Better error display
-			panic!("Boolean type expected as condition (if)");
+	fn expect_pattern_type(&mut self, pattern: &Pattern, expected: &Type, pattern_type: Type, span: Option<Span>) {
+		if !self.unify(expected, &pattern_type) {
+			let message = format!("Pattern {:?} can't match {:?}", pattern, self.resolve(expected));
+			self.error(message, span);
 		}
+	}
 
-		if let Some(instruction_list) = body {
-			instruction_list.accept(self);
+	fn check_pattern(&mut self, pattern: &Pattern, expected: &Type, span: Option<Span>) {
+		match pattern {
+			Pattern::Wildcard => {},
+			// Bound twice in the same pattern is reported by `check_bindings`
+			Pattern::Binding(name) => self.scopes.insert_var(name, Variable::new(expected.clone(), false, VariableKind::Binding, span)),
+			Pattern::Int(_) => self.expect_pattern_type(pattern, expected, Type::Int, span),
+			Pattern::Bool(_) => self.expect_pattern_type(pattern, expected, Type::Bool, span),
+			Pattern::None => {
+				let value_type = self.fresh_var();
+				self.expect_pattern_type(pattern, expected, Type::Option(Box::new(value_type)), span);
+			},
+			Pattern::Some(value) => {
+				let value_type = self.fresh_var();
+				self.expect_pattern_type(pattern, expected, Type::Option(Box::new(value_type.clone())), span);
+
+				self.check_pattern(value, &value_type, span);
+			},
+			Pattern::Ok(value) | Pattern::Err(value) => {
+				let value_type = self.fresh_var();
+				let error_type = self.fresh_var();
+				self.expect_pattern_type(pattern, expected, Type::Result(Box::new(value_type.clone()), Box::new(error_type.clone())), span);
+
+				let field_type = if let Pattern::Ok(_) = pattern { value_type } else { error_type };
+				self.check_pattern(value, &field_type, span);
+			},
+			Pattern::Variant { enum_name, variant, fields } => {
+				self.expect_pattern_type(pattern, expected, Type::Named(enum_name.clone()), span);
+
+				let mut field_types = self.variant_types(enum_name, variant, span).unwrap_or_else(|| vec![Type::Error; fields.len()]);
+				if fields.len() != field_types.len() {
+					self.error(format!("{}::{} has {} fields, got {}", enum_name, variant, field_types.len(), fields.len()), span);
+					field_types = vec![Type::Error; fields.len()];
+				}
+
+				// The bindings are declared even in a wrong pattern, their uses are not reported
+				for (field, field_type) in fields.iter().zip(field_types.iter()) {
+					self.check_pattern(field, field_type, span);
+				}
+			}
 		}
 	}
 
-	fn visit_instruction_list(&mut self, current: &Node, next: &Option<Node>) {
-		current.accept(self);
+	// Patterns of the first column that can be tested, with the types of their fields
+	fn constructors(&self, matched_type: &Type) -> Option<Vec<(Pattern, Vec<Type>)>> {
+		match matched_type {
+			Type::Bool => Some(Vec::from([(Pattern::Bool(true), Vec::new()), (Pattern::Bool(false), Vec::new())])),
+			Type::Option(value_type) => Some(Vec::from([(Pattern::None, Vec::new()), (Pattern::Some(Box::new(Pattern::Wildcard)), Vec::from([*value_type.clone()]))])),
+			Type::Result(value_type, error_type) => Some(Vec::from([
+				(Pattern::Ok(Box::new(Pattern::Wildcard)), Vec::from([*value_type.clone()])),
+				(Pattern::Err(Box::new(Pattern::Wildcard)), Vec::from([*error_type.clone()]))
+			])),
+			Type::Named(name) => self.resolve_scope_enum(name).map(|enum_def| {
+				enum_def.variant_names.iter().zip(enum_def.variant_types.iter()).map(|(variant, field_types)| {
+					(Pattern::Variant { enum_name: name.clone(), variant: variant.clone(), fields: Vec::new() }, field_types.clone())
+				}).collect()
+			}),
+			_ => None
+		}
+	}
 
-		if let Some(x) = next {
-			x.accept(self);
+	// Rows starting with `constructor` or an irrefutable pattern, with the first column replaced by its fields
+	fn specialize<'a>(rows: &[Vec<&'a Pattern>], constructor: &Pattern, arity: usize) -> Vec<Vec<&'a Pattern>> {
+		let mut specialized = Vec::new();
+
+		for row in rows.iter() {
+			let fields: Vec<&Pattern> = match (row[0], constructor) {
+				(Pattern::Wildcard | Pattern::Binding(_), _) => vec![&WILDCARD; arity],
+				(Pattern::Bool(value), Pattern::Bool(expected)) if value == expected => Vec::new(),
+				(Pattern::None, Pattern::None) => Vec::new(),
+				(Pattern::Some(value), Pattern::Some(_)) | (Pattern::Ok(value), Pattern::Ok(_)) | (Pattern::Err(value), Pattern::Err(_)) => Vec::from([value.as_ref()]),
+				(Pattern::Variant { variant, fields, .. }, Pattern::Variant { variant: expected, .. }) if variant == expected => fields.iter().collect(),
+				_ => continue
+			};
+
+			specialized.push(fields.into_iter().chain(row[1..].iter().copied()).collect());
 		}
+
+		specialized
 	}
 
-	fn visit_function_declaration(&mut self, name: &String, param_names: &Vec<String>, param_types: &Vec<Type>, return_type: &Type, body: &Option<Node>) {
-		if let Some(body_node) = body {
-			let mut function_scope = TypeScope::new();
+	// Whether every value of `types` is matched by one of the rows
+	fn is_exhaustive(&self, rows: Vec<Vec<&Pattern>>, types: &[Type]) -> bool {
+		let Some((first_type, rest_types)) = types.split_first() else {
+			return !rows.is_empty();
+		};
 
-			for (i, param) in param_names.iter().enumerate() {
-				function_scope.variables.insert(param.clone(), param_types[i]);
-			}
+		// Without constructor in the first column, splitting on them would only add wildcards (and never end on recursive enums)
+		let has_constructor = rows.iter().any(|row| matches!(row[0], Pattern::Bool(_) | Pattern::None | Pattern::Some(_) | Pattern::Ok(_) | Pattern::Err(_) | Pattern::Variant { .. }));
 
-			let caller_scopes = self.scopes.clone();
-			self.scopes = Vec::from([function_scope]);
+		match self.constructors(first_type).filter(|_| has_constructor) {
+			Some(constructors) => constructors.iter().all(|(constructor, field_types)| {
+				let specialized = TypeCheckerVisitor::specialize(&rows, constructor, field_types.len());
+				let specialized_types: Vec<Type> = field_types.iter().chain(rest_types.iter()).cloned().collect();
 
-			body_node.accept(self);
-			// TODO: Validate return type with the real returned type
+				self.is_exhaustive(specialized, &specialized_types)
+			}),
+			// Too many values to be listed, only irrefutable patterns cover them
+			None => {
+				let default = rows.into_iter()
+					.filter(|row| matches!(row[0], Pattern::Wildcard | Pattern::Binding(_)))
+					.map(|row| row[1..].to_vec())
+					.collect();
 
-			self.scopes = caller_scopes;
+				self.is_exhaustive(default, rest_types)
+			}
 		}
-		else {
-			// Empty body
-			self.result = Type::Void;
+	}
+
+	fn apply_op(&mut self, op: &Operator, original_type: Type, span: &Span) -> Type {
+		match original_type {
+			Type::Error => return Type::Error,
+			Type::Var(_) => return self.apply_unknown_op(op, original_type, span),
+			_ if self.resolve_type_param(&original_type).is_some() => return self.apply_generic_op(op, original_type, span),
+			Type::Int | Type::Float | Type::Bool | Type::String => {},
+			_ => return self.error(format!("Operators can't be applied on {:?}", original_type), Some(*span))
 		}
 
-		if self.result != *return_type {
-			// TODO: Better error display
-			panic!("{} doesn't return {:?}", name, return_type);
+		let string_op = matches!(op, Operator::Add | Operator::Equal | Operator::NotEqual |
+			Operator::Lower | Operator::LowerOrEq | Operator::Greater | Operator::GreaterOrEq);
+		if original_type == Type::String && !string_op {
+			return self.error(format!("{:?} doesn't support Type::String operands", op), Some(*span));
 		}
 
-		self.insert_function(name, return_type.clone(), param_types.clone());
-	}
-	
-	fn visit_function_call(&mut self, name: &String, args: &Vec<Node>) {
-		let result = self.resolve_scope_function(name).cloned();
-		match result.clone() {
-			Some(function_def) => {
-				if args.len() != function_def.param_types.len() {
-					panic!("Wrong number of argument for {}", name); // TODO: Better error display
+		match op {
+			Operator::Equal => Type::Bool,
+			Operator::Greater => Type::Bool,
+			Operator::GreaterOrEq => Type::Bool,
+			Operator::Lower => Type::Bool,
+			Operator::LowerOrEq => Type::Bool,
+			Operator::NotEqual => Type::Bool,
+			Operator::LogicalAnd | Operator::LogicalOr | Operator::Not => {
+				if original_type != Type::Bool {
+					return self.error(format!("{:?} only supports Type::Bool operands", op), Some(*span));
 				}
 
-				for (i, arg_node) in args.iter().enumerate() {
-					arg_node.accept(self);
+				Type::Bool
+			},
+			Operator::Modulo | Operator::Power => {
+				if original_type != Type::Int && original_type != Type::Float {
+					return self.error(format!("{:?} only supports Type::Int and Type::Float operands", op), Some(*span));
+				}
 
-					if self.result != function_def.param_types[i] {
-						panic!("Wrong argument type at {}'s call, expected {:?}, got {:?}", name, function_def.param_types[i], self.result);
-					}
+				original_type
+			},
+			Operator::BitwiseAnd | Operator::BitwiseOr | Operator::BitwiseXor |
+			Operator::ShiftLeft | Operator::ShiftRight | Operator::BitwiseNot => {
+				if original_type != Type::Int {
+					return self.error(format!("{:?} only supports Type::Int operands", op), Some(*span));
 				}
 
-				self.result = function_def.return_type;
+				Type::Int
 			},
-			_ => panic!("Undefined function {}", name) // TODO: Better error display
+			_ => original_type
 		}
 	}
-}
\ No newline at end of file
+}
+
+impl Visitor for TypeCheckerVisitor {
+	fn visit_int(&mut self, _: i64) {
+		self.result = Type::Int;
+	}
+
+	fn visit_float(&mut self, _: f64) {
+		self.result = Type::Float;
+	}
+
+	fn visit_identifier(&mut self, name: &str, span: &Span) {
+		self.result = match self.scopes.resolve_var_mut(name) {
+			Some(var) => {
+				var.used = true;
+				var.var_type.clone()
+			},
+			None => self.error(format!("Not declared identifier {}", name), Some(*span))
+		};
+	}
+
+	fn visit_bool(&mut self, _: bool) {
+		self.result = Type::Bool;
+	}
+
+	fn visit_string(&mut self, _: &str) {
+		self.result = Type::String;
+	}
+
+	fn visit_none(&mut self) {
+		let value_type = self.fresh_var();
+
+		self.result = Type::Option(Box::new(value_type));
+	}
+
+	fn visit_some(&mut self, value: &Node) {
+		value.accept(self);
+
+		self.result = Type::Option(Box::new(self.result.clone()));
+	}
+
+	fn visit_ok(&mut self, value: &Node) {
+		value.accept(self);
+		let value_type = self.result.clone();
+
+		let error_type = self.fresh_var();
+		self.result = Type::Result(Box::new(value_type), Box::new(error_type));
+	}
+
+	fn visit_err(&mut self, value: &Node) {
+		value.accept(self);
+		let error_type = self.result.clone();
+
+		let value_type = self.fresh_var();
+		self.result = Type::Result(Box::new(value_type), Box::new(error_type));
+	}
+
+	fn visit_try(&mut self, value: &Node, span: &Span) {
+		value.accept(self);
+		let result_type = self.result.clone();
+
+		match self.shallow_resolve(&result_type) {
+			Type::Error => return,
+			Type::Result(_, _) | Type::Var(_) => {},
+			_ => {
+				let message = format!("? expects a result, got {:?}", self.resolve(&result_type));
+				self.result = self.error(message, Some(*span));
+				return;
+			}
+		}
+
+		// The error is returned as is, the function must return the same error type
+		let returned_type = Type::Result(Box::new(self.fresh_var()), Box::new(self.fresh_var()));
+		let Some(return_type) = self.return_type.clone().filter(|return_type| self.unify(return_type, &returned_type)) else {
+			self.result = self.error(String::from("? can only be used in a function returning a result"), Some(*span));
+			return;
+		};
+
+		let Type::Result(_, error_type) = returned_type else {
+			unreachable!();
+		};
+
+		let value_type = self.fresh_var();
+		if !self.unify(&Type::Result(Box::new(value_type.clone()), error_type), &result_type) {
+			let message = format!("? can't propagate {:?} from a function returning {:?}", self.resolve(&result_type), self.resolve(&return_type));
+			self.result = self.error(message, Some(*span));
+			return;
+		}
+
+		self.result = value_type;
+	}
+
+	fn visit_binary_op(&mut self, op: &Operator, left: &Node, right: &Node, span: &Span) {
+		left.accept(self);
+		let lhs_type = self.result.clone();
+
+		right.accept(self);
+		let rhs_type = self.result.clone();
+
+		let unwrapped = self.check_unwrapped(&self.resolve(&lhs_type), span);
+		if !unwrapped || !self.check_unwrapped(&self.resolve(&rhs_type), span) {
+			self.result = Type::Error;
+			return;
+		}
+
+		if !self.unify(&lhs_type, &rhs_type) {
+			// No implicit conversion, mixing types requires an explicit `as`
+			let message = format!("Wrong type operand, can't mix {:?} and {:?} without a cast", self.resolve(&lhs_type), self.resolve(&rhs_type));
+			self.result = self.error(message, Some(*span));
+			return;
+		}
+
+		let operand_type = self.resolve(&lhs_type);
+		self.result = self.apply_op(op, operand_type, span);
+	}
+
+	fn visit_unary_op(&mut self, op: &Operator, right: &Node, span: &Span) {
+		right.accept(self);
+		let operand_type = self.resolve(&self.result);
+		if !self.check_unwrapped(&operand_type, span) {
+			self.result = Type::Error;
+			return;
+		}
+
+		let message = match (op, &operand_type) {
+			(Operator::Not, _) if !self.unify(&Type::Bool, &operand_type) => Some(String::from("Can't use Operator::Not on other type than Type::Bool")),
+			(Operator::Not, _) => None,
+			(_, Type::Bool) => Some(String::from("Type::Bool only support Operator::Not as unary op")),
+			(_, Type::String) => Some(format!("Operators can't be applied on {:?}", operand_type)),
+			_ => None
+		};
+
+		self.result = match message {
+			Some(message) => self.error(message, Some(*span)),
+			None => self.apply_op(op, operand_type, span)
+		};
+	}
+
+	fn visit_cast(&mut self, value: &Node, target: &Type, span: &Span) {
+		let target = self.check_type(target, span);
+
+		value.accept(self);
+		let value_type = self.resolve(&self.result);
+
+		let message = match (&value_type, &target) {
+			(Type::Error, _) | (Type::Int, Type::Float) | (Type::Float, Type::Int) | (Type::Bool, Type::Int) => None,
+			(Type::Var(_), _) => Some(format!("Can't infer the type converted to {:?}, annotate it", target)),
+			// The concrete type of a type parameter is not known at runtime
+			(from, _) if *from == Type::Void || self.resolve_type_param(from).is_some() => Some(format!("Can't cast {:?} as {:?}", value_type, target)),
+			// `none as option<int>` gives its type to `none`
+			(from, to) if !self.unify(to, from) => Some(format!("Can't cast {:?} as {:?}", value_type, target)),
+			_ => None
+		};
+
+		if let Some(message) = message {
+			self.error(message, Some(*span));
+		}
+
+		self.result = target;
+	}
+
+	fn visit_array(&mut self, elements: &[Node], span: &Span) {
+		// Inferred from the elements, or from the uses of an empty array
+		let element_type = self.fresh_var();
+
+		for element in elements.iter() {
+			element.accept(self);
+
+			if !self.unify(&element_type, &self.result.clone()) {
+				let message = format!("Array elements must have the same type, got {:?} and {:?}", self.resolve(&element_type), self.resolve(&self.result));
+				self.error(message, Some(*span));
+			}
+		}
+
+		self.result = Type::Array(Box::new(element_type));
+	}
+
+	fn visit_index(&mut self, array: &Node, index: &Node, span: &Span) {
+		array.accept(self);
+		let array_type = self.result.clone();
+
+		self.result = self.element_type(array_type, index, span);
+	}
+
+	fn visit_map(&mut self, keys: &[Node], values: &[Node], span: &Span) {
+		let key_type = self.fresh_var();
+		let value_type = self.fresh_var();
+
+		for (key, value) in keys.iter().zip(values) {
+			key.accept(self);
+			let entry_key_type = self.result.clone();
+
+			value.accept(self);
+			let entry_value_type = self.result.clone();
+
+			if !self.unify(&key_type, &entry_key_type) || !self.unify(&value_type, &entry_value_type) {
+				let message = format!("Map entries must have the same types, got {:?}: {:?} and {:?}: {:?}",
+					self.resolve(&key_type), self.resolve(&value_type), self.resolve(&entry_key_type), self.resolve(&entry_value_type));
+				self.error(message, Some(*span));
+			}
+		}
+
+		self.check_map_key(&key_type, span);
+
+		self.result = Type::Map(Box::new(key_type), Box::new(value_type));
+	}
+
+	fn visit_struct_literal(&mut self, name: &str, field_names: &[String], values: &[Node], span: &Span) {
+		let Some(struct_def) = self.resolve_scope_struct(name).cloned() else {
+			// The values are still checked for their own errors
+			for value in values.iter() {
+				value.accept(self);
+			}
+
+			self.result = self.error(format!("Undefined struct {}", name), Some(*span));
+			return;
+		};
+
+		let mut initialized = vec![false; struct_def.field_names.len()];
+		for (field, value) in field_names.iter().zip(values) {
+			value.accept(self);
+
+			let Some(position) = struct_def.field_names.iter().position(|field_name| field_name == field) else {
+				self.error(format!("{} has no field {}", name, field), Some(*span));
+				continue;
+			};
+
+			if initialized[position] {
+				self.error(format!("Field {} of {} initialized twice", field, name), Some(*span));
+			}
+			initialized[position] = true;
+
+			if !self.unify(&struct_def.field_types[position], &self.result.clone()) {
+				let message = format!("Wrong type for field {} of {}, expected {:?}, got {:?}", field, name, struct_def.field_types[position], self.resolve(&self.result));
+				self.error(message, Some(*span));
+			}
+		}
+
+		for (field_name, is_initialized) in struct_def.field_names.iter().zip(initialized) {
+			if !is_initialized {
+				self.error(format!("Missing field {} in {}", field_name, name), Some(*span));
+			}
+		}
+
+		self.result = Type::Named(String::from(name));
+	}
+
+	fn visit_field_access(&mut self, value: &Node, field: &str, span: &Span) {
+		value.accept(self);
+
+		self.result = self.field_type(&self.result.clone(), field, span);
+	}
+
+	fn visit_var_assignation(&mut self, name: &str, value: &Node, span: &Span) {
+		value.accept(self);
+
+		let var_type = self.assigned_var_type(name, span);
+		if !self.unify(&var_type, &self.result.clone()) {
+			let message = format!("Wrong type assignation to {}, expected {:?}, got {:?}", name, self.resolve(&var_type), self.resolve(&self.result));
+			self.error(message, Some(*span));
+		}
+	}
+
+	fn visit_enum_variant(&mut self, enum_name: &str, variant: &str, args: &[Node], span: &Span) {
+		let field_types = self.variant_types(enum_name, variant, Some(*span));
+		if let Some(field_types) = &field_types {
+			if args.len() != field_types.len() {
+				self.error(format!("{}::{} expects {} values, got {}", enum_name, variant, field_types.len(), args.len()), Some(*span));
+			}
+		}
+
+		for (i, arg) in args.iter().enumerate() {
+			arg.accept(self);
+
+			let Some(field_type) = field_types.as_ref().and_then(|field_types| field_types.get(i)) else {
+				continue;
+			};
+
+			if !self.unify(field_type, &self.result.clone()) {
+				let message = format!("Wrong value type for {}::{}, expected {:?}, got {:?}", enum_name, variant, field_type, self.resolve(&self.result));
+				self.error(message, Some(*span));
+			}
+		}
+
+		self.result = match field_types {
+			Some(_) => Type::Named(String::from(enum_name)),
+			None => Type::Error
+		};
+	}
+
+	fn visit_match(&mut self, value: &Node, patterns: &[Pattern], arms: &[Node], span: &Span) {
+		value.accept(self);
+		let value_type = self.result.clone();
+
+		if arms.is_empty() {
+			self.result = self.error(String::from("Match without arms"), Some(*span));
+			return;
+		}
+
+		let arm_type = self.fresh_var();
+		for (pattern, arm) in patterns.iter().zip(arms) {
+			self.check_bindings(pattern, Some(*span));
+
+			self.scopes.push_block(Scope::new());
+			self.check_pattern(pattern, &value_type, Some(*span));
+			arm.accept(self);
+			self.pop_block();
+
+			if !self.unify(&arm_type, &self.result.clone()) {
+				let message = format!("Match arms must have the same type, got {:?} and {:?}", self.resolve(&arm_type), self.resolve(&self.result));
+				self.error(message, Some(*span));
+			}
+		}
+
+		// The patterns may have given its type to the matched value
+		let value_type = self.resolve(&value_type);
+		let rows = patterns.iter().map(|pattern| Vec::from([pattern])).collect();
+		if value_type != Type::Error && !self.is_exhaustive(rows, std::slice::from_ref(&value_type)) {
+			self.error(format!("Match on {:?} is not exhaustive", value_type), Some(*span));
+		}
+
+		self.result = arm_type;
+	}
+
+	fn visit_element_assignation(&mut self, name: &str, path: &[Accessor], value: &Node, span: &Span) {
+		let mut target_type = self.assigned_var_type(name, span);
+
+		for accessor in path.iter() {
+			target_type = match accessor {
+				Accessor::Index(index) => self.element_type(target_type, index, span),
+				Accessor::Field(field) => self.field_type(&target_type, field, span)
+			};
+		}
+
+		value.accept(self);
+		if !self.unify(&target_type, &self.result.clone()) {
+			let message = format!("Wrong type assignation to {}, expected {:?}, got {:?}", name, self.resolve(&target_type), self.resolve(&self.result));
+			self.error(message, Some(*span));
+		}
+	}
+
+	fn visit_const_declaration(&mut self, name: &str, declared_type: &Type, value: &Node, span: &Span) {
+		if !self.scopes.at_top_level() {
+			self.error(format!("Constant {} must be declared at top level", name), Some(*span));
+		}
+		if self.scopes.is_declared_in_block(name) || self.scopes.is_constant(name) {
+			self.error(format!("{} is already declared in this scope", name), Some(*span));
+		}
+
+		value.accept(self);
+
+		let declared_type = self.check_type(declared_type, span);
+		if !matches!(declared_type, Type::Int | Type::Float | Type::Bool | Type::Error) {
+			self.error(format!("Constant {} must be an int, a float or a bool, got {:?}", name, declared_type), Some(*span));
+		}
+		else if !self.unify(&declared_type, &self.result.clone()) {
+			let message = format!("Declared type of {} doesn't match expression, expected {:?}, got {:?}", name, declared_type, self.resolve(&self.result));
+			self.error(message, Some(*span));
+		}
+		else if self.result != Type::Error {
+			// Only literals, operators and the previous constants can be computed before running the program
+			match OptimizerVisitor::evaluate(value, &self.constant_values) {
+				Some(constant) => { self.constant_values.insert(String::from(name), constant); },
+				None => { self.error(format!("Value of constant {} can't be computed at compile time", name), Some(*span)); }
+			}
+		}
+
+		self.scopes.insert_constant(name, Variable::new(declared_type, false, VariableKind::Constant, Some(*span)));
+	}
+
+	fn visit_var_declaration(&mut self, name: &str, mutable: bool, declared_type: &Option<Type>, value: &Node, span: &Span) {
+		value.accept(self);
+
+		if let Some(explicit_type) = declared_type {
+			let explicit_type = self.check_type(explicit_type, span);
+
+			if !self.unify(&explicit_type, &self.result.clone()) {
+				let message = format!("Declared type of {} doesn't match expression, expected {:?}, got {:?}", name, explicit_type, self.resolve(&self.result));
+				self.error(message, Some(*span));
+			}
+
+			// The annotation is trusted, the uses of the variable are checked against it
+			self.result = explicit_type;
+		}
+
+		self.declare_var(name, Variable::new(self.result.clone(), mutable, VariableKind::Variable, Some(*span)));
+	}
+
+	fn visit_return_statement(&mut self, value: &Option<Node>, span: &Span) {
+		if let Some(exp) = value {
+			exp.accept(self);
+		}
+		else {
+			self.result = Type::Void;
+		}
+
+		// The branch would have no value
+		if self.value_blocks > 0 {
+			self.error(String::from("Return in a branch of an if expression"), Some(*span));
+		}
+
+		// A top level return is the exit code, there is no function to match
+		if let Some(return_type) = self.return_type.clone() {
+			if !self.unify(&return_type, &self.result.clone()) {
+				let message = format!("Wrong return type, expected {:?}, got {:?}", self.resolve(&return_type), self.resolve(&self.result));
+				self.error(message, Some(*span));
+			}
+
+			self.has_return = true;
+		}
+	}
+
+	fn visit_if_statement(&mut self, condition: &Node, body: &Option<Node>, else_body: &Option<Node>, span: &Span) {
+		condition.accept(self);
+		if !self.unify(&Type::Bool, &self.result.clone()) {
+			let message = format!("Boolean type expected as condition (if), got {:?}", self.resolve(&self.result));
+			self.error(message, Some(*span));
+		}
+
+		// Like at runtime, the variables of the bodies are dropped at their end
+		for instruction_list in [body, else_body].into_iter().flatten() {
+			self.scopes.push_block(Scope::new());
+			instruction_list.accept(self);
+			self.pop_block();
+		}
+	}
+
+	fn visit_if_let(&mut self, pattern: &Pattern, value: &Node, body: &Option<Node>, span: &Span) {
+		value.accept(self);
+		let value_type = self.result.clone();
+
+		self.check_bindings(pattern, Some(*span));
+
+		self.scopes.push_block(Scope::new());
+		self.check_pattern(pattern, &value_type, Some(*span));
+		if let Some(instruction_list) = body {
+			instruction_list.accept(self);
+		}
+		self.pop_block();
+	}
+
+	fn visit_for_loop(&mut self, variable: &str, iterable: &Node, body: &Option<Node>, span: &Span) {
+		iterable.accept(self);
+
+		let iterable_type = self.resolve(&self.result.clone());
+		let element_type = match iterable_type {
+			_ if !self.check_unwrapped(&iterable_type, span) => Type::Error,
+			Type::Array(element_type) => *element_type,
+			Type::Map(key_type, _) => *key_type,
+			Type::Error => Type::Error,
+			Type::Var(_) => self.error(String::from("Can't infer the type of the iterated value, annotate it"), Some(*span)),
+			other => self.error(format!("{:?} can't be iterated", other), Some(*span))
+		};
+
+		// Bound in the body, like the bindings of an `if let`
+		self.scopes.push_block(Scope::new());
+		self.scopes.insert_var(variable, Variable::new(element_type, false, VariableKind::Binding, Some(*span)));
+		if let Some(instruction_list) = body {
+			instruction_list.accept(self);
+		}
+		self.pop_block();
+	}
+
+	fn visit_block(&mut self, body: &Option<Node>, value: &Node) {
+		self.scopes.push_block(Scope::new());
+		if let Some(instruction_list) = body {
+			instruction_list.accept(self);
+		}
+		value.accept(self);
+		self.pop_block();
+	}
+
+	fn visit_if_expression(&mut self, condition: &Node, then_branch: &Node, else_branch: &Node, span: &Span) {
+		condition.accept(self);
+		if !self.unify(&Type::Bool, &self.result.clone()) {
+			let message = format!("Boolean type expected as condition (if), got {:?}", self.resolve(&self.result));
+			self.error(message, Some(*span));
+		}
+
+		self.value_blocks += 1;
+		then_branch.accept(self);
+		let then_type = self.result.clone();
+		else_branch.accept(self);
+		self.value_blocks -= 1;
+
+		if !self.unify(&then_type, &self.result.clone()) {
+			let message = format!("If branches must have the same type, got {:?} and {:?}", self.resolve(&then_type), self.resolve(&self.result));
+			self.result = self.error(message, Some(*span));
+			return;
+		}
+
+		self.result = then_type;
+	}
+
+	fn visit_instruction_list(&mut self, current: &Node, next: &Option<Node>) {
+		current.accept(self);
+
+		if let Some(x) = next {
+			x.accept(self);
+		}
+	}
+
+	fn visit_function_declaration(&mut self, name: &str, type_params: &[TypeParam], param_names: &[String], param_spans: &[Span], param_types: &[Type], return_type: &Type, body: &Option<Node>, span: &Span) {
+		let mut function_scope = TypeScope::new();
+		let mut declared_type_params = Vec::new();
+
+		for (i, type_param) in type_params.iter().enumerate() {
+			if type_params[..i].iter().any(|other| other.name == type_param.name) {
+				self.error(format!("Type parameter {} declared twice in {}", type_param.name, name), Some(*span));
+			}
+
+			let mut bounds = Vec::new();
+			for bound_name in type_param.bounds.iter() {
+				match Bound::from_name(bound_name) {
+					Some(bound) => bounds.push(bound),
+					None => {
+						let expected: Vec<String> = Bound::ALL.iter().map(Bound::to_string).collect();
+						self.error(format!("Unknown bound {}, expected one of {}", bound_name, expected.join(", ")), Some(*span));
+					}
+				}
+			}
+
+			function_scope.types.type_params.insert(type_param.name.clone(), bounds.clone());
+			declared_type_params.push((type_param.name.clone(), bounds));
+		}
+
+		let caller_frame_start = self.scopes.push_frame(function_scope);
+
+		// Checked in the function scope, they can name its type parameters
+		// Omitted annotations are inferred from the body
+		let param_types: Vec<Type> = param_types.iter().zip(param_spans).map(|(param_type, param_span)| match param_type {
+			Type::Infer => self.fresh_var(),
+			_ => self.check_type(param_type, param_span)
+		}).collect();
+		let return_type = match return_type {
+			Type::Infer => self.fresh_var(),
+			_ => self.check_type(return_type, span)
+		};
+
+		for ((param, param_span), param_type) in param_names.iter().zip(param_spans).zip(param_types.iter()) {
+			self.declare_var(param, Variable::new(param_type.clone(), false, VariableKind::Parameter, Some(*param_span)));
+		}
+
+		// Declared first in its own frame to allow recursive calls, the types being inferred are shared by them
+		self.scopes.insert_function(name, FunctionType {
+			type_params: declared_type_params.clone(),
+			return_type: return_type.clone(),
+			param_types: param_types.clone()
+		});
+
+		let caller_return_type = self.return_type.replace(return_type.clone());
+		let caller_has_return = std::mem::replace(&mut self.has_return, false);
+		let caller_value_blocks = std::mem::replace(&mut self.value_blocks, 0);
+		let caller_map_keys = self.map_keys.len();
+
+		if let Some(body_node) = body {
+			body_node.accept(self);
+		}
+
+		// Without return statement, the function returns nothing
+		if !self.has_return && !self.unify(&return_type, &Type::Void) {
+			let message = format!("{} doesn't return {:?}", name, self.resolve(&return_type));
+			self.error(message, Some(*span));
+		}
+
+		self.check_map_keys(caller_map_keys);
+		self.pop_frame(caller_frame_start);
+		self.return_type = caller_return_type;
+		self.has_return = caller_has_return;
+		self.value_blocks = caller_value_blocks;
+
+		let function_type = self.generalize(name, span, &declared_type_params, &param_types, &return_type);
+		self.scopes.insert_function(name, function_type);
+
+		self.result = Type::Void;
+	}
+	
+	fn visit_function_call(&mut self, name: &str, args: &[Node], span: &Span) {
+		let result = self.scopes.resolve_function(name).cloned();
+		if result.is_none() && builtin::is_builtin(name) {
+			let param_types = builtin::signature(name).map(|(param_types, _)| param_types).unwrap_or_default();
+
+			let mut arg_types = Vec::new();
+			for (i, arg_node) in args.iter().enumerate() {
+				arg_node.accept(self);
+
+				// The key of `contains` and `remove` has the type of the map keys
+				let param_type = match (name, arg_types.first()) {
+					("contains" | "remove", Some(Type::Map(key_type, _))) if i == 1 => Some(*key_type.clone()),
+					_ => param_types.get(i).cloned()
+				};
+				if let Some(param_type) = param_type {
+					self.unify(&param_type, &self.result.clone());
+				}
+
+				let arg_type = match self.resolve(&self.result) {
+					Type::Var(_) => self.error(format!("Can't infer the type of the argument {} of {}, annotate it", i, name), Some(*span)),
+					arg_type => arg_type
+				};
+
+				arg_types.push(arg_type);
+			}
+
+			// Already reported
+			if arg_types.contains(&Type::Error) {
+				self.result = Type::Error;
+				return;
+			}
+
+			self.result = match builtin::return_type(name, &arg_types) {
+				Ok(return_type) => return_type,
+				Err(message) => self.error(message, Some(*span))
+			};
+
+			return;
+		}
+
+		let Some(function_def) = result else {
+			for arg_node in args.iter() {
+				arg_node.accept(self);
+			}
+
+			self.result = self.error(format!("Undefined function {}", name), Some(*span));
+			return;
+		};
+
+		if args.len() != function_def.param_types.len() {
+			self.error(format!("Wrong number of argument for {}, expected {}, got {}", name, function_def.param_types.len(), args.len()), Some(*span));
+		}
+
+		// Type parameters are inferred from the arguments
+		let mut instances = HashMap::new();
+		for (type_param, bounds) in function_def.type_params.iter() {
+			let instance = self.fresh_var();
+			for bound in bounds.iter() {
+				self.require_bound(&instance, *bound, span);
+			}
+
+			instances.insert(type_param.clone(), instance);
+		}
+
+		for (i, arg_node) in args.iter().enumerate() {
+			arg_node.accept(self);
+
+			let Some(param_type) = function_def.param_types.get(i) else {
+				continue;
+			};
+
+			let param_type = TypeCheckerVisitor::instantiate(param_type, &instances);
+			if !self.unify(&param_type, &self.result.clone()) {
+				let message = format!("Wrong argument type at {}'s call, expected {:?}, got {:?}", name, self.resolve(&param_type), self.resolve(&self.result));
+				self.error(message, Some(*span));
+			}
+		}
+
+		self.result = TypeCheckerVisitor::instantiate(&function_def.return_type, &instances);
+	}
+
+	fn visit_struct_declaration(&mut self, name: &str, field_names: &[String], field_types: &[Type], span: &Span) {
+		self.check_type_name(name, span);
+
+		let mut checked_types = Vec::new();
+		for (i, field) in field_names.iter().enumerate() {
+			if field_names[..i].contains(field) {
+				self.error(format!("Field {} declared twice in {}", field, name), Some(*span));
+			}
+
+			// A struct can't contain itself, it is declared after its fields are checked
+			checked_types.push(self.check_type(&field_types[i], span));
+		}
+
+		self.scopes.current_mut().types.structs.insert(String::from(name), StructType { field_names: field_names.to_vec(), field_types: checked_types });
+
+		self.result = Type::Void;
+	}
+
+	fn visit_enum_declaration(&mut self, name: &str, variant_names: &[String], variant_types: &[Vec<Type>], span: &Span) {
+		self.check_type_name(name, span);
+
+		// Declared first, a variant can hold the enum itself
+		self.scopes.current_mut().types.enums.insert(String::from(name), EnumType { variant_names: variant_names.to_vec(), variant_types: variant_types.to_vec() });
+
+		let mut checked_types = Vec::new();
+		for (i, variant) in variant_names.iter().enumerate() {
+			if variant_names[..i].contains(variant) {
+				self.error(format!("Variant {} declared twice in {}", variant, name), Some(*span));
+			}
+
+			checked_types.push(variant_types[i].iter().map(|field_type| self.check_type(field_type, span)).collect());
+		}
+
+		// Declared again without the undefined types
+		self.scopes.current_mut().types.enums.insert(String::from(name), EnumType { variant_names: variant_names.to_vec(), variant_types: checked_types });
+
+		self.result = Type::Void;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::lexer::Lexer;
+	use crate::parser::Parser;
+
+	fn check(program: &str) -> Vec<String> {
+		let mut lexer = Lexer::new(program);
+		let mut parser = Parser::new(&mut lexer);
+
+		match TypeCheckerVisitor::new().check(&parser.ast().unwrap()) {
+			Ok(()) => Vec::new(),
+			Err(errors) => errors.iter().map(|error| error.message.clone()).collect()
+		}
+	}
+
+	fn warnings(program: &str) -> Vec<String> {
+		let mut lexer = Lexer::new(program);
+		let mut parser = Parser::new(&mut lexer);
+
+		let mut checker = TypeCheckerVisitor::new();
+		let _ = checker.check(&parser.ast().unwrap());
+
+		checker.warnings().iter().map(|warning| warning.message.clone()).collect()
+	}
+
+	#[test]
+	fn valid_program() {
+		let errors = check("
+			fn add(a, b) {
+				return a + b;
+			}
+			let total = add(1, 2) + add(3, 4);
+		");
+
+		assert_eq!(errors, Vec::<String>::new());
+	}
+
+	#[test]
+	fn collects_every_error() {
+		let errors = check("
+			let a = 1 + true;
+			let b = missing(2);
+			if 3 {
+				let c = \"text\" * \"twice\";
+			}
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Wrong type operand, can't mix Int and Bool without a cast"),
+			String::from("Undefined function missing"),
+			String::from("Boolean type expected as condition (if), got Int"),
+			String::from("Product doesn't support Type::String operands")
+		]));
+	}
+
+	#[test]
+	fn error_type_suppresses_cascades() {
+		let errors = check("
+			let a = undefined + 1;
+			let b = a * 2;
+			let c: Unknown = b;
+			let d = c.field[0];
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Not declared identifier undefined"),
+			String::from("Undefined type Unknown")
+		]));
+	}
+
+	#[test]
+	fn error_locations() {
+		let mut lexer = Lexer::new("let a = missing;\nfn add(x, y) {\n\treturn x + y;\n}\nlet b = add(true, false);\nfn wrong() -> int {\n\treturn true;\n}\nif 1 {\n}\nfn unknown() {\n\treturn [];\n}\nstruct S { x: int }\nstruct S { y: int }");
+		let mut parser = Parser::new(&mut lexer);
+
+		let errors = TypeCheckerVisitor::new().check(&parser.ast().unwrap()).unwrap_err();
+		let lines: Vec<(String, usize)> = errors.iter().map(|error| (error.message.clone(), error.span.unwrap().line)).collect();
+
+		assert_eq!(lines, Vec::from([
+			(String::from("Not declared identifier missing"), 0),
+			(String::from("Bool is not Num"), 4),
+			(String::from("Wrong return type, expected Int, got Bool"), 6),
+			(String::from("Boolean type expected as condition (if), got Int"), 8),
+			(String::from("Can't infer the return type of unknown, annotate it"), 10),
+			(String::from("Type S already declared"), 14)
+		]));
+	}
+
+	#[test]
+	fn parameter_locations() {
+		let mut lexer = Lexer::new("fn twice(a: int,\n\ta: int,\n\tb: Unknown) {\n}");
+		let mut parser = Parser::new(&mut lexer);
+
+		let errors = TypeCheckerVisitor::new().check(&parser.ast().unwrap()).unwrap_err();
+
+		assert_eq!(errors, Vec::from([
+			TypeError::new(String::from("Undefined type Unknown"), Some(Span::new(2, 1))),
+			TypeError::new(String::from("a is already declared in this scope"), Some(Span::new(1, 1)))
+		]));
+	}
+
+	#[test]
+	fn logical_operators() {
+		let errors = check("
+			fn both(x, y) {
+				return x && y;
+			}
+			let valid = both(true, 1 < 2) || !false;
+			let ints = 1 && 2;
+			let floats = 1.5 || 2.5;
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("LogicalAnd only supports Type::Bool operands"),
+			String::from("LogicalOr only supports Type::Bool operands")
+		]));
+	}
+
+	#[test]
+	fn if_block_scoping() {
+		let errors = check("
+			let x = 1;
+			if true {
+				let x = \"shadowed\";
+				let inner = x + \"!\";
+			}
+			let sum = x + 1;
+			let outside = inner;
+		");
+
+		assert_eq!(errors, Vec::from([String::from("Not declared identifier inner")]));
+	}
+
+	#[test]
+	fn for_loop() {
+		let errors = check("
+			let mut total = 0;
+			for value in [1, 2, 3] {
+				total += value;
+			}
+			for flag in [true] {
+				total += flag;
+				flag = false;
+			}
+			for digit in 42 {
+				total += digit;
+			}
+			for name in map { \"alice\": 30 } {
+				total += len(name);
+			}
+			let outside = value;
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Wrong type operand, can't mix Int and Bool without a cast"),
+			String::from("Can't assign to immutable binding flag declared at line 6, copy it in a `let mut` variable to modify it"),
+			String::from("Int can't be iterated"),
+			String::from("Not declared identifier value")
+		]));
+	}
+
+	#[test]
+	fn redeclaration() {
+		let errors = check("
+			let x = 1;
+			let x = 2;
+			fn twice(a: int, a: int) -> int {
+				return a;
+			}
+			if true {
+				let x = \"nested\";
+				let y = x;
+				let y = 3;
+			}
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("x is already declared in this scope"),
+			String::from("a is already declared in this scope"),
+			String::from("y is already declared in this scope")
+		]));
+	}
+
+	#[test]
+	fn unused_variables() {
+		let warnings = warnings("
+			fn foo(arg1: int, arg2: int, _arg3: int) {
+				let inside = arg2;
+				let read = 1;
+				let mut assigned = 2;
+				assigned = read;
+			}
+			let top_level = match some(1) {
+				some(value) => 0,
+				none => 1
+			};
+		");
+
+		assert_eq!(warnings, Vec::from([
+			String::from("Unused parameter arg1"),
+			String::from("Unused variable assigned"),
+			String::from("Unused variable inside"),
+			String::from("Unused binding value")
+		]));
+	}
+
+	#[test]
+	fn unused_warning_locations() {
+		let mut lexer = Lexer::new("fn foo(arg1: int,\n\targ2: int) {\n\tlet inside = arg2;\n}");
+		let mut parser = Parser::new(&mut lexer);
+
+		let mut checker = TypeCheckerVisitor::new();
+		let _ = checker.check(&parser.ast().unwrap());
+
+		assert_eq!(checker.warnings(), &[
+			TypeWarning::new(String::from("Unused parameter arg1"), Some(Span::new(0, 7))),
+			TypeWarning::new(String::from("Unused variable inside"), Some(Span::new(2, 1)))
+		]);
+	}
+
+	#[test]
+	fn immutable_bindings() {
+		let errors = check("
+			let fixed = [1, 2];
+			let mut counter = 0;
+			counter = counter + 1;
+			fixed = [3];
+			fixed[0] = 3;
+			fn reset(value: int) {
+				value = 0;
+			}
+			if let some(inner) = some(counter) {
+				inner = 2;
+			}
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Can't assign to immutable variable fixed declared at line 2, declare it with `let mut fixed` to allow it"),
+			String::from("Can't assign to immutable variable fixed declared at line 2, declare it with `let mut fixed` to allow it"),
+			String::from("Can't assign to immutable parameter value declared at line 7, copy it in a `let mut` variable to modify it"),
+			String::from("Can't assign to immutable binding inner declared at line 10, copy it in a `let mut` variable to modify it")
+		]));
+	}
+
+	#[test]
+	fn compound_assignation() {
+		let errors = check("
+			let mut total = 1;
+			total += 2;
+			total -= 0.5;
+			let fixed = [1];
+			fixed[0] *= 3;
+			let mut flag = true;
+			flag += 1;
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Wrong type operand, can't mix Int and Float without a cast"),
+			String::from("Can't assign to immutable variable fixed declared at line 5, declare it with `let mut fixed` to allow it"),
+			String::from("Wrong type operand, can't mix Bool and Int without a cast")
+		]));
+	}
+
+	#[test]
+	fn if_expression() {
+		let errors = check("
+			fn sign(x: int) -> int {
+				let result = if x > 0 { 1 } else if x < 0 { -1 } else { 0 };
+				return result;
+			}
+			let mixed = if true { 1 } else { \"one\" };
+			let inner = if 1 { let hidden = 2; hidden } else { 3 };
+			let outside = hidden;
+			fn early(x: int) -> int {
+				return if x > 0 { return 1; 2 } else { 3 };
+			}
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("If branches must have the same type, got Int and String"),
+			String::from("Boolean type expected as condition (if), got Int"),
+			String::from("Not declared identifier hidden"),
+			String::from("Return in a branch of an if expression")
+		]));
+	}
+
+	#[test]
+	fn constants() {
+		let errors = check("
+			const SIZE: int = 10;
+			const AREA: int = SIZE * SIZE + 1;
+			const RATIO: float = 1.5 * 2.0;
+			fn area() -> int {
+				return AREA;
+			}
+			let count = 3;
+			const LIMIT: int = count + 1;
+			const NAME: string = \"jpl\";
+			const BROKEN: int = 1 / 0;
+			const SIZE: int = 20;
+			let AREA = 1;
+			SIZE = 5;
+			if true {
+				const INNER: bool = true;
+			}
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Value of constant LIMIT can't be computed at compile time"),
+			String::from("Constant NAME must be an int, a float or a bool, got String"),
+			String::from("Value of constant BROKEN can't be computed at compile time"),
+			String::from("SIZE is already declared in this scope"),
+			String::from("AREA is already declared in this scope"),
+			String::from("Can't assign to immutable constant SIZE declared at line 12, copy it in a `let mut` variable to modify it"),
+			String::from("Constant INNER must be declared at top level")
+		]));
+	}
+
+	#[test]
+	fn type_inference() {
+		let errors = check("
+			fn identity(x) {
+				return x;
+			}
+			let number = identity(1) + 2;
+			let text = identity(\"a\") + \"b\";
+			fn empty() {
+				return [];
+			}
+			fn add(a, b) {
+				return a + b;
+			}
+			let sum = add(1.5, 2.5);
+			let wrong = add(true, false);
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Can't infer the return type of empty, annotate it"),
+			String::from("Bool is not Num")
+		]));
+	}
+
+	#[test]
+	fn match_exhaustiveness() {
+		let errors = check("
+			enum S { A(int), B, C }
+			let s = S::B;
+			let partial = match s {
+				S::A(x) => x,
+				S::B => 0
+			};
+			let covered = match s {
+				S::A(1) => 1,
+				S::A(_) => 2,
+				_ => 0
+			};
+			let numbers = match 3 {
+				0 => true,
+				1 => false
+			};
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Match on Named(\"S\") is not exhaustive"),
+			String::from("Match on Int is not exhaustive")
+		]));
+	}
+
+	#[test]
+	fn option_unwrapping() {
+		let errors = check("
+			let maybe = some(1);
+			let plain = maybe + 1;
+			if let some(value) = maybe {
+				let inside = value + 1;
+			}
+			let outside = value;
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Option(Int) can't be used as a plain value, unwrap it with match or if let"),
+			String::from("Not declared identifier value")
+		]));
+	}
+
+	#[test]
+	fn error_propagation() {
+		let errors = check("
+			fn half(text: string) -> result<int, string> {
+				let number = parse_int(text)?;
+				return ok(number / 2);
+			}
+			fn plain(text: string) -> int {
+				return parse_int(text)?;
+			}
+			fn wrong_error(value: result<int, int>) -> result<int, string> {
+				let number = value?;
+				return ok(number);
+			}
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("? can only be used in a function returning a result"),
+			String::from("? can't propagate Result(Int, Int) from a function returning Result(Int, String)")
+		]));
+	}
+
+	#[test]
+	fn generic_bounds() {
+		let errors = check("
+			fn max<T: Ord>(a: T, b: T) -> T {
+				if a > b {
+					return a;
+				}
+				return b;
+			}
+			fn double<T: Num>(value: T) -> T {
+				return value + value;
+			}
+			fn unbounded<T>(value: T) -> T {
+				return value * value;
+			}
+			let bigger = max(1, 2);
+			let smaller = max(1.5, 2.5);
+			let doubled = double(true);
+			let ordered = max(true, false);
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Product requires Named(\"T\") to be bounded by Num"),
+			String::from("Bool is not Num"),
+			String::from("Bool is not Ord")
+		]));
+	}
+
+	#[test]
+	fn map_keys() {
+		let errors = check("
+			fn unknown<T: Hash>(value: T) -> T {
+				return value;
+			}
+			fn generic<T: Ord>(key: T) -> map[T, int] {
+				return map { key: 1 };
+			}
+			fn inferred(key) {
+				let _counts = map { key: 1 };
+			}
+			let ages = map { \"alice\": 30 };
+			let prices = map { 1.5: 2 };
+			let mut empty = map {};
+			empty[2.5] = true;
+		");
+
+		assert_eq!(errors, Vec::from([
+			String::from("Unknown bound Hash, expected one of Eq, Ord, Num"),
+			String::from("Named(\"T\") can't be a map key"),
+			String::from("Named(\"T\") can't be a map key"),
+			String::from("Can't infer the type of the map keys, annotate it"),
+			String::from("Float can't be a map key"),
+			String::from("Float can't be a map key")
+		]));
+	}
+}
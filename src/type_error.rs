@@ -0,0 +1,50 @@
+use std::fmt;
+
+use crate::span::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeError {
+	pub message: String,
+	// Statements without span are only reported by message
+	pub span: Option<Span>
+}
+
+impl TypeError {
+	pub fn new(message: String, span: Option<Span>) -> TypeError {
+		TypeError {
+			message,
+			span
+		}
+	}
+}
+
+impl fmt::Display for TypeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Type error: {}", self.message)?;
+
+		if let Some(span) = self.span {
+			write!(f, "\n\tat {}", span)?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn display_with_span() {
+		let error = TypeError::new(String::from("Undefined function foo"), Some(Span::new(4, 2)));
+
+		assert_eq!(error.to_string(), "Type error: Undefined function foo\n\tat line 5");
+	}
+
+	#[test]
+	fn display_without_span() {
+		let error = TypeError::new(String::from("Not declared identifier x"), None);
+
+		assert_eq!(error.to_string(), "Type error: Not declared identifier x");
+	}
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use crate::span::Span;
+
+// Reported by the type checker without stopping the program
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeWarning {
+	pub message: String,
+	pub span: Option<Span>
+}
+
+impl TypeWarning {
+	pub fn new(message: String, span: Option<Span>) -> TypeWarning {
+		TypeWarning {
+			message,
+			span
+		}
+	}
+}
+
+impl fmt::Display for TypeWarning {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Warning: {}", self.message)?;
+
+		if let Some(span) = self.span {
+			write!(f, "\n\tat {}", span)?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn display() {
+		let warning = TypeWarning::new(String::from("Unused variable inside"), None);
+
+		assert_eq!(warning.to_string(), "Warning: Unused variable inside");
+	}
+}
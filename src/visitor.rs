@@ -1,21 +1,45 @@
-use crate::node::Node;
+use crate::node::{Node, Accessor, Pattern, TypeParam};
 use crate::operator::Operator;
 use crate::r#type::Type;
+use crate::span::Span;
 
 pub trait Visitor {
 	fn visit_int(&mut self, value: i64);
 	fn visit_float(&mut self, value: f64);
-	fn visit_identifier(&mut self, name: &String);
+	fn visit_identifier(&mut self, name: &str, span: &Span);
 	fn visit_bool(&mut self, value: bool);
-	fn visit_binary_op(&mut self, op: &Operator, left: &Node, right: &Node);
-	fn visit_unary_op(&mut self, op: &Operator, right: &Node);
-	fn visit_var_assignation(&mut self, name: &String, value: &Node);
-	fn visit_var_declaration(&mut self, name: &String, declared_type: &Option<Type>, value: &Node);
-	fn visit_return_statement(&mut self, value: &Option<Node>);
-	fn visit_if_statement(&mut self, condition: &Node, body: &Option<Node>);
+	fn visit_string(&mut self, value: &str);
+	fn visit_binary_op(&mut self, op: &Operator, left: &Node, right: &Node, span: &Span);
+	fn visit_unary_op(&mut self, op: &Operator, right: &Node, span: &Span);
+	fn visit_cast(&mut self, value: &Node, target: &Type, span: &Span);
+	fn visit_array(&mut self, elements: &[Node], span: &Span);
+	fn visit_map(&mut self, keys: &[Node], values: &[Node], span: &Span);
+	fn visit_none(&mut self);
+	fn visit_some(&mut self, value: &Node);
+	fn visit_ok(&mut self, value: &Node);
+	fn visit_err(&mut self, value: &Node);
+	fn visit_try(&mut self, value: &Node, span: &Span);
+	fn visit_index(&mut self, array: &Node, index: &Node, span: &Span);
+	fn visit_struct_literal(&mut self, name: &str, field_names: &[String], values: &[Node], span: &Span);
+	fn visit_field_access(&mut self, value: &Node, field: &str, span: &Span);
+	fn visit_enum_variant(&mut self, enum_name: &str, variant: &str, args: &[Node], span: &Span);
+	fn visit_match(&mut self, value: &Node, patterns: &[Pattern], arms: &[Node], span: &Span);
+	fn visit_var_assignation(&mut self, name: &str, value: &Node, span: &Span);
+	fn visit_element_assignation(&mut self, name: &str, path: &[Accessor], value: &Node, span: &Span);
+	fn visit_const_declaration(&mut self, name: &str, declared_type: &Type, value: &Node, span: &Span);
+	fn visit_var_declaration(&mut self, name: &str, mutable: bool, declared_type: &Option<Type>, value: &Node, span: &Span);
+	fn visit_return_statement(&mut self, value: &Option<Node>, span: &Span);
+	fn visit_if_statement(&mut self, condition: &Node, body: &Option<Node>, else_body: &Option<Node>, span: &Span);
+	fn visit_if_let(&mut self, pattern: &Pattern, value: &Node, body: &Option<Node>, span: &Span);
+	fn visit_for_loop(&mut self, variable: &str, iterable: &Node, body: &Option<Node>, span: &Span);
+	fn visit_block(&mut self, body: &Option<Node>, value: &Node);
+	fn visit_if_expression(&mut self, condition: &Node, then_branch: &Node, else_branch: &Node, span: &Span);
 	fn visit_instruction_list(&mut self, current: &Node, next: &Option<Node>);
-	fn visit_function_declaration(&mut self, name: &String, param_names: &Vec<String>, param_types: &Vec<Type>, return_type: &Type, body: &Option<Node>);
-	fn visit_function_call(&mut self, name: &String, args: &Vec<Node>);
+	#[allow(clippy::too_many_arguments)]
+	fn visit_function_declaration(&mut self, name: &str, type_params: &[TypeParam], param_names: &[String], param_spans: &[Span], param_types: &[Type], return_type: &Type, body: &Option<Node>, span: &Span);
+	fn visit_function_call(&mut self, name: &str, args: &[Node], span: &Span);
+	fn visit_struct_declaration(&mut self, name: &str, field_names: &[String], field_types: &[Type], span: &Span);
+	fn visit_enum_declaration(&mut self, name: &str, variant_names: &[String], variant_types: &[Vec<Type>], span: &Span);
 }
 
 pub trait Visitable {